@@ -0,0 +1,447 @@
+use crate::render::test::{Keystroke, TestResult};
+use crate::render::util::DAY;
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+/// Weight of a key's miss rate in its weakness score, relative to its normalized latency.
+const MISS_WEIGHT: f32 = 5.;
+
+/// Minimum number of presses before a key or bigram is given a weakness score. Below this, it is
+/// treated as average.
+const MIN_PRESSES: usize = 5;
+
+/// Number of recent tests weaknesses are computed from, so that scores follow improvement.
+const WEAKNESS_HISTORY: usize = 50;
+
+/// Aggregated performance on a single key, or on a sequence of keys ending in that key.
+#[derive(Default, Clone, Copy)]
+pub struct KeyStats {
+    /// Number of times this key was expected and typed.
+    pub presses: usize,
+    /// Number of times a different key was typed instead.
+    pub misses: usize,
+    /// Number of presses with a measured latency.
+    pub timed: usize,
+    /// Total time taken to press this key, measured from the previous keystroke.
+    pub latency: Duration,
+}
+
+impl KeyStats {
+    /// Fraction of presses that were misses.
+    pub fn miss_rate(&self) -> f32 {
+        if self.presses == 0 {
+            return 0.;
+        }
+        self.misses as f32 / self.presses as f32
+    }
+
+    /// Average time taken to correctly press this key.
+    pub fn average_latency(&self) -> Duration {
+        if self.timed == 0 {
+            return Duration::ZERO;
+        }
+        self.latency / self.timed as u32
+    }
+}
+
+/// Aggregates per-key performance over the keystroke logs of the given tests. Keys are grouped by
+/// the physical key they are typed on, so `A` and `a` count towards the same key.
+pub fn key_stats<'a>(tests: impl IntoIterator<Item = &'a TestResult>) -> HashMap<char, KeyStats> {
+    let mut keys: HashMap<char, KeyStats> = HashMap::new();
+    for test in tests {
+        let mut last = None;
+        for keystroke in &test.keystrokes {
+            if keystroke.typed.is_none() {
+                last = Some(keystroke.time);
+                continue;
+            }
+            let key = keys.entry(base_key(keystroke.expected)).or_default();
+            key.presses += 1;
+            if keystroke.is_hit() {
+                if let Some(last) = last {
+                    key.timed += 1;
+                    key.latency += keystroke.time.saturating_sub(last);
+                }
+            } else {
+                key.misses += 1;
+            }
+            last = Some(keystroke.time);
+        }
+    }
+    keys
+}
+
+/// A character that was typed in place of another.
+pub struct Confusion {
+    /// Character that was expected.
+    pub expected: char,
+    /// Character that was typed instead.
+    pub typed: char,
+    /// Number of times `typed` was typed when `expected` was expected.
+    pub count: usize,
+    /// Fraction of the times `expected` was expected that `typed` was typed instead.
+    pub rate: f32,
+}
+
+/// Aggregates every substitution in the keystroke logs of the given tests, most frequent first.
+pub fn confusions<'a>(tests: impl IntoIterator<Item = &'a TestResult>) -> Vec<Confusion> {
+    let mut expected: HashMap<char, usize> = HashMap::new();
+    let mut pairs: HashMap<(char, char), usize> = HashMap::new();
+    for keystroke in tests.into_iter().flat_map(|test| &test.keystrokes) {
+        let Some(typed) = keystroke.typed else {
+            continue;
+        };
+        *expected.entry(keystroke.expected).or_default() += 1;
+        if typed != keystroke.expected {
+            *pairs.entry((keystroke.expected, typed)).or_default() += 1;
+        }
+    }
+    let mut confusions = pairs
+        .into_iter()
+        .map(|((e, typed), count)| Confusion {
+            expected: e,
+            typed,
+            count,
+            rate: count as f32 / expected[&e] as f32,
+        })
+        .collect::<Vec<Confusion>>();
+    confusions.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then(b.rate.total_cmp(&a.rate))
+            .then(a.expected.cmp(&b.expected))
+    });
+    confusions
+}
+
+/// Aggregates performance on every sequence of `n` characters over the keystroke logs of the given
+/// tests. Each keystroke counts towards the sequence of expected characters ending at its position,
+/// and is timed only when it directly follows a keystroke on the previous character. Sequences
+/// containing whitespace are skipped, and sequences are lowercased.
+pub fn ngram_stats<'a>(
+    tests: impl IntoIterator<Item = &'a TestResult>,
+    n: usize,
+) -> HashMap<String, KeyStats> {
+    let mut ngrams: HashMap<String, KeyStats> = HashMap::new();
+    for test in tests {
+        let chars = test.phrase.chars().collect::<Vec<char>>();
+        let mut last: Option<&Keystroke> = None;
+        for keystroke in &test.keystrokes {
+            let prev = last.replace(keystroke);
+            if keystroke.typed.is_none() || keystroke.position + 1 < n {
+                continue;
+            }
+            let Some(gram) = chars.get(keystroke.position + 1 - n..=keystroke.position) else {
+                continue;
+            };
+            if gram.iter().any(|c| c.is_whitespace()) {
+                continue;
+            }
+            let stats = ngrams
+                .entry(gram.iter().map(|c| c.to_ascii_lowercase()).collect())
+                .or_default();
+            stats.presses += 1;
+            if keystroke.is_hit() {
+                let prev =
+                    prev.filter(|p| p.typed.is_some() && p.position + 1 == keystroke.position);
+                if let Some(prev) = prev {
+                    stats.timed += 1;
+                    stats.latency += keystroke.time.saturating_sub(prev.time);
+                }
+            } else {
+                stats.misses += 1;
+            }
+        }
+    }
+    ngrams
+}
+
+/// Per-key and per-bigram weakness scores, computed from recent history. A score of 1 is average;
+/// higher scores are slower or more often missed.
+pub struct Weakness {
+    keys: HashMap<char, f32>,
+    bigrams: HashMap<String, f32>,
+}
+
+impl Weakness {
+    /// Computes weakness scores from the most recent tests in `history`.
+    pub fn from_history(history: &[TestResult]) -> Self {
+        let recent = &history[history.len().saturating_sub(WEAKNESS_HISTORY)..];
+        Self {
+            keys: scores(key_stats(recent)),
+            bigrams: scores(ngram_stats(recent, 2)),
+        }
+    }
+
+    /// Scores a word by the average weakness of its keys and bigrams.
+    pub fn score(&self, word: &str) -> f32 {
+        let chars = word.chars().collect::<Vec<char>>();
+        let keys = chars
+            .iter()
+            .map(|c| *self.keys.get(&base_key(*c)).unwrap_or(&1.))
+            .collect::<Vec<f32>>();
+        let bigrams = chars
+            .windows(2)
+            .map(|w| {
+                let bigram = w.iter().map(|c| c.to_ascii_lowercase()).collect::<String>();
+                *self.bigrams.get(&bigram).unwrap_or(&1.)
+            })
+            .collect::<Vec<f32>>();
+        let mean = |v: &[f32]| {
+            if v.is_empty() {
+                1.
+            } else {
+                v.iter().sum::<f32>() / v.len() as f32
+            }
+        };
+        (mean(&keys) + mean(&bigrams)) / 2.
+    }
+}
+
+/// Scores every entry with enough presses by its latency relative to the average, plus its
+/// weighted miss rate relative to the average.
+fn scores<K: std::hash::Hash + Eq>(stats: HashMap<K, KeyStats>) -> HashMap<K, f32> {
+    let stats = stats
+        .into_iter()
+        .filter(|(_, s)| s.presses >= MIN_PRESSES && s.timed > 0)
+        .collect::<Vec<(K, KeyStats)>>();
+    if stats.is_empty() {
+        return HashMap::new();
+    }
+    let n = stats.len() as f32;
+    let mean_latency = stats
+        .iter()
+        .map(|(_, s)| s.average_latency().as_secs_f32())
+        .sum::<f32>()
+        / n;
+    let mean_miss_rate = stats.iter().map(|(_, s)| s.miss_rate()).sum::<f32>() / n;
+    stats
+        .into_iter()
+        .map(|(k, s)| {
+            let latency = s.average_latency().as_secs_f32() / mean_latency.max(f32::EPSILON);
+            let misses = 1. + (s.miss_rate() - mean_miss_rate) * MISS_WEIGHT;
+            (k, ((latency + misses) / 2.).max(0.))
+        })
+        .collect()
+}
+
+/// Activity on a single day.
+#[derive(Default, Clone, Copy)]
+pub struct DayStats {
+    /// Number of tests taken.
+    pub tests: usize,
+    /// Best net WPM.
+    pub best: f32,
+}
+
+/// Aggregates activity by day, keyed by days since the Unix epoch in UTC. Tests without a
+/// timestamp are skipped.
+pub fn daily_activity<'a>(
+    tests: impl IntoIterator<Item = &'a TestResult>,
+) -> BTreeMap<u64, DayStats> {
+    let mut days: BTreeMap<u64, DayStats> = BTreeMap::new();
+    for test in tests {
+        let Some(timestamp) = test.timestamp else {
+            continue;
+        };
+        let day = days.entry(timestamp / DAY).or_default();
+        day.tests += 1;
+        day.best = day.best.max(test.wpm.1);
+    }
+    days
+}
+
+/// Computes the current and longest streaks of consecutive days with activity, in days. The
+/// current streak is kept alive until the end of the day after the last active day, so that it
+/// isn't broken before the user has had a chance to practice `today`.
+pub fn streaks(days: &BTreeMap<u64, DayStats>, today: u64) -> (usize, usize) {
+    let (mut current, mut longest, mut last) = (0, 0, None);
+    for day in days.keys() {
+        current = match last {
+            Some(last) if last + 1 == *day => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        last = Some(*day);
+    }
+    match last {
+        Some(last) if last + 1 >= today => (current, longest),
+        _ => (0, longest),
+    }
+}
+
+/// Gets the key a character is typed on, on a US QWERTY keyboard. Shifted characters map to their
+/// unshifted counterparts.
+pub fn base_key(c: char) -> char {
+    const SHIFTED: [(char, char); 21] = [
+        ('~', '`'),
+        ('!', '1'),
+        ('@', '2'),
+        ('#', '3'),
+        ('$', '4'),
+        ('%', '5'),
+        ('^', '6'),
+        ('&', '7'),
+        ('*', '8'),
+        ('(', '9'),
+        (')', '0'),
+        ('_', '-'),
+        ('+', '='),
+        ('{', '['),
+        ('}', ']'),
+        ('|', '\\'),
+        (':', ';'),
+        ('"', '\''),
+        ('<', ','),
+        ('>', '.'),
+        ('?', '/'),
+    ];
+    SHIFTED
+        .iter()
+        .find(|(shifted, _)| *shifted == c)
+        .map(|(_, base)| *base)
+        .unwrap_or(c.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{test::TestMode, wordlist::Wordlist};
+
+    /// Creates a keystroke typed at `ms` milliseconds.
+    fn keystroke(ms: u64, expected: char, typed: Option<char>) -> Keystroke {
+        Keystroke {
+            time: Duration::from_millis(ms),
+            position: 0,
+            expected,
+            typed,
+            backspace: typed.is_none(),
+            correction: false,
+        }
+    }
+
+    /// Creates a test result from a keystroke log.
+    fn test_result(keystrokes: Vec<Keystroke>) -> TestResult {
+        typed_result("", keystrokes)
+    }
+
+    /// Creates a test result over `phrase`, typing it at 100ms per key with a miss on `miss`.
+    fn typed_phrase(phrase: &str, miss: Option<usize>, slow: Option<usize>) -> TestResult {
+        let mut time = 0;
+        let keystrokes = phrase
+            .chars()
+            .enumerate()
+            .map(|(position, expected)| {
+                time += if slow == Some(position) { 500 } else { 100 };
+                let typed = if miss == Some(position) {
+                    '#'
+                } else {
+                    expected
+                };
+                Keystroke {
+                    position,
+                    ..keystroke(time, expected, Some(typed))
+                }
+            })
+            .collect();
+        typed_result(phrase, keystrokes)
+    }
+
+    /// Creates a test result over `phrase` from a keystroke log.
+    fn typed_result(phrase: &str, keystrokes: Vec<Keystroke>) -> TestResult {
+        TestResult::builder(TestMode::Words(1), Wordlist::English1k)
+            .phrase(phrase)
+            .time(Duration::from_secs(1))
+            .keystrokes(keystrokes)
+            .build()
+    }
+
+    #[test]
+    fn key_stats_aggregate() {
+        let test = test_result(vec![
+            keystroke(0, 'a', Some('a')),
+            keystroke(100, 'B', Some('x')),
+            keystroke(200, 'b', None),
+            keystroke(500, 'b', Some('b')),
+        ]);
+        let keys = key_stats([&test]);
+
+        // the first keystroke has no latency, since nothing came before it
+        let a = keys[&'a'];
+        assert_eq!((a.presses, a.misses, a.timed), (1, 0, 0));
+
+        // shifted and unshifted presses count towards the same key
+        let b = keys[&'b'];
+        assert_eq!((b.presses, b.misses), (2, 1));
+        assert_eq!(b.miss_rate(), 0.5);
+        assert_eq!(b.average_latency(), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn shifted_keys_map_to_base() {
+        assert_eq!(base_key('A'), 'a');
+        assert_eq!(base_key('?'), '/');
+        assert_eq!(base_key('7'), '7');
+    }
+
+    #[test]
+    fn streaks_are_counted() {
+        let days = [1, 2, 3, 10, 11]
+            .into_iter()
+            .map(|day| (day, DayStats::default()))
+            .collect::<BTreeMap<u64, DayStats>>();
+        assert_eq!(streaks(&days, 11), (2, 3));
+        assert_eq!(streaks(&days, 12), (2, 3));
+        assert_eq!(streaks(&days, 13), (0, 3));
+        assert_eq!(streaks(&BTreeMap::new(), 13), (0, 0));
+    }
+
+    #[test]
+    fn confusions_aggregate() {
+        let test = test_result(vec![
+            keystroke(0, 't', Some('r')),
+            keystroke(100, 't', None),
+            keystroke(200, 't', Some('t')),
+            keystroke(300, 't', Some('t')),
+            keystroke(400, 't', Some('r')),
+            keystroke(500, 'a', Some('s')),
+        ]);
+        let confusions = confusions([&test]);
+        assert_eq!(confusions.len(), 2);
+        assert_eq!((confusions[0].expected, confusions[0].typed), ('t', 'r'));
+        assert_eq!(confusions[0].count, 2);
+        assert_eq!(confusions[0].rate, 0.5);
+        assert_eq!(confusions[1].rate, 1.);
+    }
+
+    #[test]
+    fn ngram_stats_aggregate() {
+        let test = typed_phrase("the then", Some(6), Some(2));
+        let bigrams = ngram_stats([&test], 2);
+
+        // sequences spanning whitespace are skipped
+        assert!(!bigrams.contains_key("e "));
+        let th = bigrams["th"];
+        assert_eq!((th.presses, th.misses, th.timed), (2, 0, 2));
+        let he = bigrams["he"];
+        assert_eq!((he.presses, he.misses, he.timed), (2, 1, 1));
+        assert_eq!(he.average_latency(), Duration::from_millis(500));
+        assert_eq!(ngram_stats([&test], 3)["the"].presses, 2);
+    }
+
+    #[test]
+    fn weak_keys_score_higher() {
+        // 'z' is always slow, and 'q' is missed once
+        let history = (0..10)
+            .map(|i| typed_phrase("abzq", (i == 0).then_some(3), Some(2)))
+            .collect::<Vec<TestResult>>();
+        let weakness = Weakness::from_history(&history);
+        assert!(weakness.score("zz") > weakness.score("ab"));
+        assert!(weakness.score("qq") > weakness.score("bb"));
+
+        // unseen words are average
+        assert_eq!(weakness.score("xy"), 1.);
+    }
+}
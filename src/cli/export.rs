@@ -0,0 +1,364 @@
+use crate::{
+    profile::{Profile, ProfileStatistics},
+    render::{
+        test::{TestMode, TestResult},
+        util::format_date,
+        wordlist::Wordlist,
+    },
+};
+use clap::{arg, ArgMatches, Command};
+use serde_derive::Serialize;
+use std::{
+    fs::File,
+    io::{stdout, Write},
+};
+
+/// Formats history can be exported to.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    /// One row per test. Statistics are written to a separate file.
+    Csv,
+    /// A single document holding statistics and history.
+    Json,
+}
+
+/// Builds the `export` subcommand.
+pub fn command() -> Command {
+    Command::new("export")
+        .about("Exports profile history and statistics to CSV or JSON")
+        .arg(
+            arg!(--format <FORMAT> "Format to export to")
+                .value_parser(["csv", "json"])
+                .default_value("json"),
+        )
+        .arg(
+            arg!(--out <FILE> "Writes to the specified file instead of standard output")
+                // csv statistics are written next to the export, so it needs a file
+                .required_if_eq("format", "csv"),
+        )
+}
+
+/// Runs the `export` subcommand on the profile at `profile`.
+pub fn run(args: &ArgMatches, profile: &str) -> Result<(), std::io::Error> {
+    let profile = super::read_profile(profile)?;
+    let format = match args.get_one::<String>("format").map(String::as_str) {
+        Some("csv") => Format::Csv,
+        _ => Format::Json,
+    };
+    let out = args.get_one::<String>("out");
+
+    // write history, along with statistics for formats that can hold both
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(stdout()),
+    };
+    export(&profile, format, &mut writer)?;
+    writer.flush()?;
+
+    // csv holds a single table, so statistics go in a file alongside the history
+    if let (Format::Csv, Some(path)) = (format, out) {
+        let stats_path = stats_path(path);
+        let mut writer = csv::Writer::from_path(&stats_path)?;
+        for row in stats_rows(&profile) {
+            writer.serialize(row).map_err(std::io::Error::other)?;
+        }
+        writer.flush()?;
+        eprintln!("Wrote statistics to '{stats_path}'.");
+    }
+    Ok(())
+}
+
+/// Writes the history of `profile` in the given format. JSON exports include statistics too.
+pub fn export(profile: &Profile, format: Format, out: impl Write) -> Result<(), std::io::Error> {
+    let records = profile.get_history().iter().map(ExportRecord::from);
+    match format {
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for mut record in records {
+                // csv cells can't hold nested data, so series are embedded as json
+                record.keystrokes = record.keystrokes.to_string().into();
+                record.timeline = record.timeline.to_string().into();
+                writer.serialize(record).map_err(std::io::Error::other)?;
+            }
+            writer.flush()?;
+        }
+        Format::Json => {
+            let document = ExportDocument {
+                stats: stats_rows(profile),
+                history: records.collect(),
+            };
+            serde_json::to_writer_pretty(out, &document).map_err(std::io::Error::other)?;
+        }
+    }
+    Ok(())
+}
+
+/// Gets the path statistics are written to, next to a CSV export at `path`.
+fn stats_path(path: &str) -> String {
+    let stem = path.strip_suffix(".csv").unwrap_or(path);
+    format!("{stem}.stats.csv")
+}
+
+/// A full JSON export.
+#[derive(Serialize)]
+struct ExportDocument {
+    /// Statistics across all tests, followed by statistics for each kind of test.
+    stats: Vec<ExportStats>,
+    /// Every test, in the order they were taken.
+    history: Vec<ExportRecord>,
+}
+
+/// Statistics over a set of tests.
+#[derive(Serialize)]
+pub(super) struct ExportStats {
+    /// Kind of test these statistics are over, or "all".
+    kind: String,
+    /// Number of tests.
+    tests: u64,
+    /// Average gross WPM.
+    average_gross_wpm: f32,
+    /// Average net WPM.
+    average_net_wpm: f32,
+    /// Average accuracy.
+    average_accuracy: f32,
+    /// Best net WPM.
+    best_wpm: f32,
+    /// Accuracy of the test the best WPM was achieved on, when known.
+    best_accuracy: Option<f32>,
+}
+
+impl ExportStats {
+    fn new(kind: String, stats: &ProfileStatistics, best_accuracy: Option<f32>) -> Self {
+        Self {
+            kind,
+            tests: stats.total_tests,
+            average_gross_wpm: stats.average_gross_wpm,
+            average_net_wpm: stats.average_net_wpm,
+            average_accuracy: stats.average_accuracy,
+            best_wpm: stats.pb,
+            best_accuracy,
+        }
+    }
+}
+
+/// Gets statistics over every test in `profile`, followed by statistics for each kind of test.
+pub(super) fn stats_rows(profile: &Profile) -> Vec<ExportStats> {
+    let history = profile.get_history();
+    let stats = profile.get_stats();
+    let mut rows = vec![ExportStats::new("all".into(), stats, None)];
+    for pb in &stats.pbs {
+        let tests = history.iter().filter(|test| test.kind() == pb.kind);
+        let kind_stats = ProfileStatistics::from_tests(tests);
+        rows.push(ExportStats::new(
+            pb.kind.to_string(),
+            &kind_stats,
+            Some(pb.accuracy),
+        ));
+    }
+    rows
+}
+
+/// A single test result, with its mode and wordlist flattened into plain columns.
+#[derive(Serialize)]
+pub(super) struct ExportRecord {
+    /// Time the test was completed, in seconds since the Unix epoch.
+    timestamp: Option<u64>,
+    /// Date the test was completed, in UTC.
+    date: Option<String>,
+    /// Mode, without its parameters.
+    mode: &'static str,
+    /// Word count of words, adaptive and drill tests.
+    words: Option<usize>,
+    /// Duration of time tests, in seconds.
+    seconds: Option<u64>,
+    /// Length of quote tests.
+    quote_length: Option<String>,
+    /// Language of code tests.
+    language: Option<String>,
+    /// File custom tests were taken on.
+    file: Option<String>,
+    /// Hash of the text custom tests were taken on.
+    text_hash: Option<u64>,
+    /// Sequence drilled by drill tests.
+    ngram: Option<String>,
+    /// Wordlist name, for modes that draw from one.
+    wordlist: Option<String>,
+    /// True if the wordlist was user-supplied, for modes that draw from one.
+    user_wordlist: Option<bool>,
+    /// True if punctuation was included.
+    punctuation: bool,
+    /// True if numbers were included.
+    numbers: bool,
+    /// Test length in words.
+    length: usize,
+    /// Phrase the test was taken on.
+    phrase: String,
+    /// Source of the phrase.
+    source: Option<String>,
+    /// Hit count.
+    hits: usize,
+    /// Miss count.
+    misses: usize,
+    /// Total time taken, in seconds.
+    time: f64,
+    /// Gross WPM.
+    gross_wpm: f32,
+    /// Net WPM.
+    net_wpm: f32,
+    /// Raw WPM.
+    raw_wpm: f32,
+    /// Accuracy.
+    accuracy: f32,
+    /// Every keystroke made during the test.
+    keystrokes: serde_json::Value,
+    /// Live WPM and errors, sampled once per second.
+    timeline: serde_json::Value,
+}
+
+impl From<&TestResult> for ExportRecord {
+    fn from(test: &TestResult) -> Self {
+        let mut record = Self {
+            timestamp: test.timestamp,
+            date: test.timestamp.map(format_date),
+            mode: "",
+            words: None,
+            seconds: None,
+            quote_length: None,
+            language: None,
+            file: None,
+            text_hash: None,
+            ngram: None,
+            wordlist: None,
+            user_wordlist: None,
+            punctuation: test.modifiers.punctuation,
+            numbers: test.modifiers.numbers,
+            length: test.length,
+            phrase: test.phrase.clone(),
+            source: test.source.clone(),
+            hits: test.hits,
+            misses: test.misses,
+            time: test.time.as_secs_f64(),
+            gross_wpm: test.wpm.0,
+            net_wpm: test.wpm.1,
+            raw_wpm: test.raw_wpm,
+            accuracy: test.accuracy,
+            keystrokes: serde_json::to_value(&test.keystrokes).unwrap_or_default(),
+            timeline: serde_json::to_value(&test.timeline).unwrap_or_default(),
+        };
+
+        // wordlist, which is only meaningful for some modes
+        if test.mode.uses_wordlist() {
+            record.wordlist = Some(test.wordlist.name());
+            record.user_wordlist = Some(matches!(test.wordlist, Wordlist::User(_)));
+        }
+
+        // flatten mode
+        use TestMode::*;
+        match &test.mode {
+            Words(words) => {
+                record.mode = "words";
+                record.words = Some(*words);
+            }
+            Time(time) => {
+                record.mode = "time";
+                record.seconds = Some(time.as_secs());
+            }
+            Quote(length) => {
+                record.mode = "quote";
+                record.quote_length = Some(length.to_string());
+            }
+            Code(language) => {
+                record.mode = "code";
+                record.language = Some(language.to_string());
+            }
+            Custom { name, hash } => {
+                record.mode = "custom";
+                record.file = Some(name.clone());
+                record.text_hash = Some(*hash);
+            }
+            Adaptive(words) => {
+                record.mode = "adaptive";
+                record.words = Some(*words);
+            }
+            Drill { ngram, words } => {
+                record.mode = "drill";
+                record.words = Some(*words);
+                record.ngram = Some(ngram.clone());
+            }
+        }
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn profile() -> Profile {
+        let mut profile = Profile::default();
+        let test = TestResult::builder(
+            TestMode::Drill {
+                ngram: "th".into(),
+                words: 2,
+            },
+            Wordlist::English1k,
+        )
+        .length(2)
+        .phrase("the, \"other\"")
+        .counts(12, 1)
+        .time(Duration::from_secs(3))
+        .timestamp(1_792_281_600)
+        .build();
+        profile.record(test);
+        profile.update_stats();
+        profile
+    }
+
+    #[test]
+    fn csv_has_flattened_columns() {
+        let mut out = vec![];
+        export(&profile(), Format::Csv, &mut out).unwrap();
+        let mut reader = csv::Reader::from_reader(&out[..]);
+        let headers = reader.headers().unwrap().clone();
+        let row = reader.records().next().unwrap().unwrap();
+        let get = |column: &str| &row[headers.iter().position(|h| h == column).unwrap()];
+        assert_eq!(get("mode"), "drill");
+        assert_eq!(get("ngram"), "th");
+        assert_eq!(get("words"), "2");
+        assert_eq!(get("seconds"), "");
+        assert_eq!(get("wordlist"), "English1k");
+        assert_eq!(get("date"), "2026-10-18");
+        assert_eq!(get("phrase"), "the, \"other\"");
+        assert_eq!(get("keystrokes"), "[]");
+    }
+
+    #[test]
+    fn wordlist_is_omitted_for_modes_without_one() {
+        let test = TestResult::builder(
+            TestMode::Custom {
+                name: "notes.txt".into(),
+                hash: 1,
+            },
+            Wordlist::English1k,
+        )
+        .phrase("notes")
+        .build();
+        let record = ExportRecord::from(&test);
+        assert_eq!(record.wordlist, None);
+        assert_eq!(record.user_wordlist, None);
+    }
+
+    #[test]
+    fn json_has_stats_and_history() {
+        let mut out = vec![];
+        export(&profile(), Format::Json, &mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(value["stats"][0]["kind"], "all");
+        assert_eq!(value["stats"][0]["tests"], 1);
+        assert_eq!(value["stats"].as_array().unwrap().len(), 2);
+        assert_eq!(value["history"][0]["mode"], "drill");
+        assert_eq!(value["history"][0]["hits"], 12);
+        assert!(value["history"][0]["keystrokes"].is_array());
+    }
+}
@@ -0,0 +1,52 @@
+use super::{export::ExportRecord, print_table, read_profile};
+use crate::render::util::format_date;
+use clap::{arg, ArgMatches, Command};
+
+/// Builds the `history` subcommand.
+pub fn command() -> Command {
+    Command::new("history")
+        .about("Prints tests from profile history, oldest first")
+        .arg(
+            arg!(--last <N> "Prints only the N most recent tests")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(arg!(--json "Prints tests as JSON"))
+}
+
+/// Runs the `history` subcommand on the profile at `profile`.
+pub fn run(args: &ArgMatches, profile: &str) -> Result<(), std::io::Error> {
+    let profile = read_profile(profile)?;
+    let history = profile.get_history();
+    let skip = args
+        .get_one::<usize>("last")
+        .map_or(0, |last| history.len().saturating_sub(*last));
+    let tests = history.iter().enumerate().skip(skip);
+
+    // json holds the same records as exports do
+    if args.get_flag("json") {
+        let records = tests
+            .map(|(_, test)| ExportRecord::from(test))
+            .collect::<Vec<ExportRecord>>();
+        println!("{}", serde_json::to_string_pretty(&records)?);
+        return Ok(());
+    }
+
+    let rows = tests
+        .map(|(idx, test)| {
+            vec![
+                (idx + 1).to_string(),
+                test.timestamp.map_or(String::from("-"), format_date),
+                test.kind().to_string(),
+                format!("{:.1}wpm", test.wpm.1),
+                format!("{:.1}wpm", test.raw_wpm),
+                format!("{:.1}%", test.accuracy),
+                format!("{:.1}s", test.time.as_secs_f32()),
+            ]
+        })
+        .collect::<Vec<Vec<String>>>();
+    print_table(
+        &["#", "date", "test", "net", "raw", "accuracy", "time"],
+        &rows,
+    );
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use crate::{
+    profile::Profile,
+    render::{
+        quote::QuoteLength,
+        test::{Modifiers, TestMode, TestResult},
+        wordlist::Wordlist,
+    },
+};
+use clap::{arg, ArgMatches, Command};
+use serde_derive::Deserialize;
+use std::{io::Read, time::Duration};
+
+/// Builds the `import` subcommand.
+pub fn command() -> Command {
+    Command::new("import")
+        .about("Imports test history from other typing tests into the profile")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("monkeytype")
+                .about("Imports results from a Monkeytype CSV export")
+                .arg(arg!(<FILE> "CSV file exported from Monkeytype's account page")),
+        )
+}
+
+/// Runs the `import` subcommand on the profile at `profile`, which is created if it doesn't
+/// exist yet.
+pub fn run(args: &ArgMatches, profile_path: &str) -> Result<(), std::io::Error> {
+    let mut profile = Profile::read_or_default(profile_path)?;
+
+    let summary = match args.subcommand() {
+        Some(("monkeytype", sub)) => {
+            let file = sub.get_one::<String>("FILE").unwrap();
+            import_monkeytype(&mut profile, std::fs::File::open(file)?)?
+        }
+        _ => unreachable!("unknown import source"),
+    };
+
+    if summary.imported > 0 {
+        profile.update_stats();
+        profile.write_to(profile_path)?;
+    }
+    println!("{summary}");
+    Ok(())
+}
+
+/// Counts of what happened to each result in an import.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Results added to the profile.
+    pub imported: usize,
+    /// Results already in the profile.
+    pub duplicates: usize,
+    /// Results in modes WPM has no equivalent for.
+    pub unsupported: usize,
+    /// Results in languages without a matching wordlist.
+    pub unknown_languages: usize,
+}
+
+impl std::fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Imported {} tests", self.imported)?;
+        if self.duplicates > 0 {
+            write!(f, ", skipped {} already imported", self.duplicates)?;
+        }
+        if self.unsupported > 0 {
+            write!(f, ", skipped {} in unsupported modes", self.unsupported)?;
+        }
+        if self.unknown_languages > 0 {
+            write!(
+                f,
+                ", skipped {} in languages without a matching wordlist",
+                self.unknown_languages
+            )?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// A row of a Monkeytype CSV export. Only the columns WPM has a use for are read.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MonkeytypeRow {
+    wpm: f32,
+    raw_wpm: f32,
+    acc: f32,
+    /// Counts of correct, incorrect, extra and missed characters, separated by semicolons.
+    #[serde(default)]
+    char_stats: String,
+    mode: String,
+    /// Parameter of the mode: word count, seconds, or quote ID.
+    mode2: String,
+    /// Length group of quotes, from 0 (short) to 3 (thicc), or -1 for other modes.
+    #[serde(default)]
+    quote_length: Option<i64>,
+    /// Test duration, in seconds.
+    test_duration: f64,
+    #[serde(default)]
+    punctuation: bool,
+    #[serde(default)]
+    numbers: bool,
+    #[serde(default)]
+    language: String,
+    /// Time the test was completed, in milliseconds since the Unix epoch.
+    timestamp: u64,
+}
+
+/// Reasons a row of an export can't be imported.
+enum Unsupported {
+    /// WPM has no equivalent of the mode.
+    Mode,
+    /// WPM has no wordlist matching the language.
+    Language,
+}
+
+impl MonkeytypeRow {
+    /// Converts this row into an equivalent test result. Quotes don't use a wordlist, so they are
+    /// recorded with `quote_wordlist`, like quotes taken in WPM record the configured one.
+    fn to_result(&self, quote_wordlist: &Wordlist) -> Result<TestResult, Unsupported> {
+        let count = |mode2: &str| mode2.parse().map_err(|_| Unsupported::Mode);
+        let mode = match self.mode.as_str() {
+            "words" => TestMode::Words(count(&self.mode2)?),
+            "time" => TestMode::Time(Duration::from_secs(count(&self.mode2)? as u64)),
+            "quote" => TestMode::Quote(match self.quote_length {
+                Some(0) => QuoteLength::Short,
+                Some(1) => QuoteLength::Medium,
+                Some(2 | 3) => QuoteLength::Long,
+                _ => QuoteLength::Any,
+            }),
+            _ => return Err(Unsupported::Mode),
+        };
+
+        // the bundled quotes are all english
+        let wordlist = match mode {
+            TestMode::Quote(_) if self.language == "english" => quote_wordlist.clone(),
+            TestMode::Quote(_) => return Err(Unsupported::Language),
+            _ => language_wordlist(&self.language).ok_or(Unsupported::Language)?,
+        };
+        let time = Duration::from_secs_f64(self.test_duration.max(0.));
+
+        // characters left incorrect and extra characters both count as misses here
+        let chars = self
+            .char_stats
+            .split(';')
+            .map(|v| v.parse::<usize>().unwrap_or(0))
+            .chain(std::iter::repeat(0))
+            .take(4)
+            .collect::<Vec<usize>>();
+        let (hits, misses) = (chars[0], chars[1] + chars[2]);
+        let length = match mode {
+            TestMode::Words(words) => words,
+            _ => (self.wpm * time.as_secs_f32() / 60.).round() as usize,
+        };
+
+        // keep monkeytype's own metrics, which account for things the character counts don't
+        let mut result = TestResult::builder(mode, wordlist)
+            .length(length)
+            .modifiers(Modifiers {
+                punctuation: self.punctuation,
+                numbers: self.numbers,
+            })
+            .counts(hits, misses)
+            .time(time)
+            .timestamp(self.timestamp / 1000)
+            .build();
+        if hits + misses == 0 {
+            result.wpm.0 = self.raw_wpm;
+        }
+        result.wpm.1 = self.wpm;
+        result.raw_wpm = self.raw_wpm;
+        result.accuracy = self.acc;
+        Ok(result)
+    }
+}
+
+/// Maps a Monkeytype language onto the matching built-in wordlist, if there is one.
+fn language_wordlist(language: &str) -> Option<Wordlist> {
+    Some(match language {
+        "english_1k" => Wordlist::English1k,
+        "english_5k" => Wordlist::English5k,
+        "english_10k" => Wordlist::English10k,
+        "english_commonly_misspelled" => Wordlist::EnglishCommonMisspelled,
+        "code_c++" => Wordlist::CodeCPP,
+        "code_c" => Wordlist::CodeC,
+        "code_javascript" => Wordlist::CodeJS,
+        _ => return None,
+    })
+}
+
+/// Adds the results in a Monkeytype CSV export to `profile`, then orders its history by when
+/// tests were taken. Results already in the profile are skipped, so the same export can be
+/// imported again after it grows. Statistics are not updated.
+pub fn import_monkeytype(
+    profile: &mut Profile,
+    csv: impl Read,
+) -> Result<ImportSummary, std::io::Error> {
+    let mut summary = ImportSummary::default();
+    let quote_wordlist = Wordlist::from(profile.get_config().get_select("wordlist"));
+    let mut results = vec![];
+    for row in csv::Reader::from_reader(csv).deserialize::<MonkeytypeRow>() {
+        let row = row.map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("not a Monkeytype export: {e}"),
+            )
+        })?;
+        match row.to_result(&quote_wordlist) {
+            Ok(result) => results.push(result),
+            Err(Unsupported::Mode) => summary.unsupported += 1,
+            Err(Unsupported::Language) => summary.unknown_languages += 1,
+        }
+    }
+
+    // exports list the newest tests first, and may overlap with tests already in the profile
+    for result in results.into_iter().rev() {
+        if profile
+            .get_history()
+            .iter()
+            .any(|test| test.is_same_test(&result))
+        {
+            summary.duplicates += 1;
+        } else {
+            profile.record(result);
+            summary.imported += 1;
+        }
+    }
+    profile.sort_history();
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\
+_id,isPb,wpm,acc,rawWpm,consistency,charStats,mode,mode2,quoteLength,restartCount,testDuration,afkDuration,incompleteWordsTyped,punctuation,numbers,language,funbox,difficulty,lazyMode,blindMode,bailedOut,tags,timestamp
+b,false,80.5,97.1,84.2,75.3,402;8;2;0,time,30,-1,0,30,0,0,true,false,english_1k,none,normal,false,false,false,,1792281660000
+a,true,72,95.5,75,70.1,120;4;1;1,words,25,-1,0,20.25,0,0,false,false,english_5k,none,normal,false,false,false,,1792281600000
+c,false,60,99,61,80,0;0;0;0,zen,,-1,0,40,0,0,false,false,english,none,normal,false,false,false,,1792281700000
+d,false,65,98,66,80,50;1;0;0,words,10,-1,0,9.5,0,0,false,false,german,none,normal,false,false,false,,1792281650000
+e,false,70,96,72,78,300;12;0;0,quote,1234,1,0,50,0,0,false,false,english,none,normal,false,false,false,,1792281500000
+";
+
+    #[test]
+    fn monkeytype_results_are_imported_once() {
+        // a profile with a test taken after every test in the export
+        let mut profile = Profile::default();
+        profile.record(
+            TestResult::builder(TestMode::Words(10), Wordlist::English1k)
+                .time(Duration::from_secs(5))
+                .timestamp(1_792_281_800)
+                .build(),
+        );
+        let summary = import_monkeytype(&mut profile, EXPORT.as_bytes()).unwrap();
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: 3,
+                duplicates: 0,
+                unsupported: 1,
+                unknown_languages: 1,
+            }
+        );
+
+        // history is ordered by when tests were taken, with modes and wordlists mapped
+        let history = profile.get_history();
+        let timestamps = history
+            .iter()
+            .map(|test| test.timestamp.unwrap())
+            .collect::<Vec<u64>>();
+        assert_eq!(
+            timestamps,
+            vec![1_792_281_500, 1_792_281_600, 1_792_281_660, 1_792_281_800]
+        );
+        assert!(history[0].mode == TestMode::Quote(QuoteLength::Medium));
+        assert!(history[1].mode == TestMode::Words(25));
+        assert_eq!(history[1].wordlist, Wordlist::English5k);
+        assert!(history[2].mode == TestMode::Time(Duration::from_secs(30)));
+        assert_eq!(history[2].wordlist, Wordlist::English1k);
+        assert!(history[2].modifiers.punctuation);
+        assert_eq!(history[2].wpm.1, 80.5);
+        assert_eq!(history[2].raw_wpm, 84.2);
+        assert_eq!(history[2].accuracy, 97.1);
+
+        // importing again adds nothing
+        let summary = import_monkeytype(&mut profile, EXPORT.as_bytes()).unwrap();
+        assert_eq!(summary.imported, 0);
+        assert_eq!(summary.duplicates, 3);
+        assert_eq!(profile.get_history().len(), 4);
+    }
+}
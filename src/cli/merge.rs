@@ -0,0 +1,35 @@
+use super::read_profile;
+use clap::{arg, ArgMatches, Command};
+
+/// Builds the `merge` subcommand.
+pub fn command() -> Command {
+    Command::new("merge")
+        .about("Merges the history of two profiles into a new profile")
+        .arg(arg!(<A> "First profile"))
+        .arg(arg!(<B> "Second profile"))
+        .arg(arg!(--out <FILE> "File to write the merged profile to").required(true))
+        .arg(
+            arg!(--config <PROFILE> "Profile whose configuration is kept")
+                .value_parser(["a", "b"])
+                .default_value("a"),
+        )
+}
+
+/// Runs the `merge` subcommand. Both profiles must exist.
+pub fn run(args: &ArgMatches) -> Result<(), std::io::Error> {
+    let a = read_profile(args.get_one::<String>("A").unwrap())?;
+    let b = read_profile(args.get_one::<String>("B").unwrap())?;
+    let out = args.get_one::<String>("out").unwrap();
+    let (a_tests, b_tests) = (a.get_history().len(), b.get_history().len());
+
+    let merged = match args.get_one::<String>("config").map(String::as_str) {
+        Some("b") => b.merge(a),
+        _ => a.merge(b),
+    };
+    merged.write_to(out)?;
+    println!(
+        "Merged {a_tests} and {b_tests} tests into {} in '{out}'.",
+        merged.get_history().len()
+    );
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use crate::profile::Profile;
+use std::io::ErrorKind;
+
+/// Export of profile history and statistics to CSV and JSON.
+pub mod export;
+
+/// Import of history from other typing tests.
+pub mod import;
+
+/// Merging of profiles.
+pub mod merge;
+
+/// Printing of profile statistics.
+pub mod stats;
+
+/// Printing of test history.
+pub mod history;
+
+/// Running of single tests from the command line.
+pub mod run;
+
+/// Reads the profile at `path` for a subcommand. Unlike the app, which starts a fresh profile,
+/// subcommands fail when there is no profile to read.
+pub fn read_profile(path: &str) -> Result<Profile, std::io::Error> {
+    Profile::read_from(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => {
+            std::io::Error::new(ErrorKind::NotFound, format!("no profile found at '{path}'"))
+        }
+        _ => e,
+    })
+}
+
+/// Prints rows of cells as a plain text table, with a header and columns padded to line up.
+pub fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths = header.iter().map(|h| h.len()).collect::<Vec<usize>>();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: Vec<&str>| {
+        let padded = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<String>>();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(header.to_vec());
+    for row in rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
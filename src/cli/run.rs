@@ -0,0 +1,88 @@
+use super::export::ExportRecord;
+use crate::{
+    profile::Profile,
+    render::{
+        test::{Modifiers, TestMode, TestRenderer},
+        util::clear,
+        wordlist::Wordlist,
+    },
+};
+use clap::{arg, ArgGroup, ArgMatches, Command};
+use crossterm::{
+    cursor::Show,
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+use std::{io::stdout, time::Duration};
+
+/// Builds the `run` subcommand.
+pub fn command() -> Command {
+    Command::new("run")
+        .about("Runs a single test, saves its result to the profile, then exits")
+        .arg(
+            arg!(--words <N> "Runs a test of N words")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .arg(
+            arg!(--time <SECONDS> "Runs a test lasting the given number of seconds")
+                .value_parser(clap::value_parser!(u64).range(1..)),
+        )
+        .group(ArgGroup::new("mode").args(["words", "time"]).required(true))
+        .arg(arg!(--wordlist <NAME> "Wordlist to use, instead of the configured one"))
+        .arg(arg!(--json "Prints the result as JSON"))
+}
+
+/// Runs the `run` subcommand on the profile at `profile_path`, which is created if it doesn't
+/// exist yet.
+pub fn run(args: &ArgMatches, profile_path: &str) -> Result<(), std::io::Error> {
+    let mut profile = Profile::read_or_default(profile_path)?;
+
+    // build the test, failing on bad wordlists before touching the terminal
+    let mode = match (args.get_one::<u64>("words"), args.get_one::<u64>("time")) {
+        (Some(words), _) => TestMode::Words(*words as usize),
+        (_, Some(time)) => TestMode::Time(Duration::from_secs(*time)),
+        _ => unreachable!("a mode is required"),
+    };
+    let wordlist = match args.get_one::<String>("wordlist") {
+        Some(name) => Wordlist::from(name.as_str()),
+        None => profile.get_config().get_select("wordlist").into(),
+    };
+    let modifiers = Modifiers::from_config(profile.get_config());
+    let (phrase, source) = mode.phrase(&wordlist, modifiers, profile.get_history())?;
+
+    // take the test
+    enable_raw_mode()?;
+    let result = TestRenderer::new(wordlist, phrase, mode).render(profile.get_config());
+    disable_raw_mode()?;
+    let mut stdout = stdout();
+    clear(&mut stdout);
+    execute!(stdout, Show)?;
+
+    // if user abandoned test, there's nothing to save
+    let Some(mut result) = result? else {
+        return Ok(());
+    };
+    result.source = source;
+    result.modifiers = modifiers;
+
+    // print the result, then record it
+    if args.get_flag("json") {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&ExportRecord::from(&result))?
+        );
+    } else {
+        let pb = profile.get_pb(&result.kind()).map_or(0., |pb| pb.wpm);
+        println!(
+            "{}: {:.1}wpm net, {:.1}wpm raw, {:.1}% accuracy{}",
+            result.kind(),
+            result.wpm.1,
+            result.raw_wpm,
+            result.accuracy,
+            if result.wpm.1 > pb { " (new pb!)" } else { "" }
+        );
+    }
+    profile.record(result);
+    profile.update_stats();
+    profile.write_to(profile_path)
+}
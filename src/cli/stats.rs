@@ -0,0 +1,49 @@
+use super::{export::stats_rows, print_table, read_profile};
+use clap::{arg, ArgMatches, Command};
+
+/// Builds the `stats` subcommand.
+pub fn command() -> Command {
+    Command::new("stats")
+        .about("Prints profile statistics and personal bests")
+        .arg(arg!(--json "Prints statistics as JSON"))
+}
+
+/// Runs the `stats` subcommand on the profile at `profile`.
+pub fn run(args: &ArgMatches, profile: &str) -> Result<(), std::io::Error> {
+    let profile = read_profile(profile)?;
+
+    // json holds the same rows as exports do
+    if args.get_flag("json") {
+        let json = serde_json::to_string_pretty(&stats_rows(&profile))?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let stats = profile.get_stats();
+    print_table(
+        &["tests", "avg net", "avg gross", "avg accuracy", "pb"],
+        &[vec![
+            stats.total_tests.to_string(),
+            format!("{:.1}wpm", stats.average_net_wpm),
+            format!("{:.1}wpm", stats.average_gross_wpm),
+            format!("{:.1}%", stats.average_accuracy),
+            format!("{:.1}wpm", stats.pb),
+        ]],
+    );
+    if !stats.pbs.is_empty() {
+        println!();
+        let rows = stats
+            .pbs
+            .iter()
+            .map(|pb| {
+                vec![
+                    pb.kind.to_string(),
+                    format!("{:.1}wpm", pb.wpm),
+                    format!("{:.1}%", pb.accuracy),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        print_table(&["test", "pb", "accuracy"], &rows);
+    }
+    Ok(())
+}
@@ -14,7 +14,7 @@ impl Default for Config {
     fn default() -> Self {
         use ConfigValue::*;
         let mut map = IndexMap::new();
-        vec![
+        [
             ("show performance indicator".into(), Bool(true)),
             ("show live words per minute".into(), Bool(true)),
             ("show recent tests".into(), Bool(true)),
@@ -34,10 +34,13 @@ impl Default for Config {
                     min: 1,
                 },
             ),
+            ("include punctuation".into(), Bool(false)),
+            ("include numbers".into(), Bool(false)),
+            ("require indentation".into(), Bool(false)),
             (
                 "wordlist".into(),
                 Select {
-                    options: Wordlist::iter().map(|v| format!("{v:?}")).collect(),
+                    options: Wordlist::all().iter().map(Wordlist::name).collect(),
                     selected: 0,
                 },
             ),
@@ -51,6 +54,14 @@ impl Default for Config {
 }
 
 impl Config {
+    /// Adds any values that are missing from this configuration with their defaults. Profiles
+    /// saved by older versions will not contain values that have been added since.
+    pub fn fill_defaults(&mut self) {
+        for (key, value) in Config::default().map {
+            self.map.entry(key).or_insert(value);
+        }
+    }
+
     /// Get raw config values by key.
     pub fn get(&self, key: impl AsRef<str>) -> &ConfigValue {
         let key = key.as_ref();
@@ -101,6 +112,21 @@ impl Config {
         }
     }
 
+    /// Replace the options of a select config value, keeping the selected option if it is still
+    /// available. Will panic if called on other variants.
+    pub fn set_options(&mut self, key: impl AsRef<str>, new_options: Vec<String>) {
+        let key = key.as_ref();
+        if let ConfigValue::Select { options, selected } = self.get_mut(key) {
+            *selected = options
+                .get(*selected)
+                .and_then(|current| new_options.iter().position(|v| v == current))
+                .unwrap_or(0);
+            *options = new_options;
+        } else {
+            panic!("set_options called on non-select configuration item");
+        }
+    }
+
     /// Set the given key to the given value.
     pub fn set(&mut self, key: impl Into<String>, value: impl Into<ConfigValue>) {
         let key = key.into();
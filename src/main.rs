@@ -3,20 +3,90 @@ use std::{io::stdout, panic::PanicHookInfo};
 use clap::{arg, Command};
 use crossterm::{
     cursor::Show,
-    queue,
-    terminal::{disable_raw_mode, enable_raw_mode},
+    execute, queue,
+    terminal::{disable_raw_mode, enable_raw_mode, is_raw_mode_enabled},
 };
 
+mod analysis;
+mod cli;
 mod config;
 mod profile;
 mod render;
 
-fn main() -> Result<(), std::io::Error> {
+fn main() {
+    if let Err(e) = run() {
+        // leave the terminal usable, in case the error interrupted the app
+        if is_raw_mode_enabled().unwrap_or(false) {
+            let _ = disable_raw_mode();
+            let _ = execute!(stdout(), Show);
+        }
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+/// Runs the app, or the subcommand given on the command line.
+fn run() -> Result<(), std::io::Error> {
     // get args
     let args = Command::new("WPM")
-        .arg(arg!(--profile <PATH> "Runs the app with the specified profile"))
+        .arg(arg!(--profile <PATH> "Uses the specified profile").global(true))
         .arg(arg!(--"no-profile" "Runs the app without a profile to save to"))
+        .arg(arg!(--file <PATH> "Starts with a typing test on the text of the specified file"))
+        .arg(
+            arg!(--"restore-backup" [N] "Restores the profile from its Nth most recent backup (default 1), then exits")
+                .value_parser(clap::value_parser!(usize))
+                .default_missing_value("1"),
+        )
+        .subcommand(cli::export::command())
+        .subcommand(cli::import::command())
+        .subcommand(cli::merge::command())
+        .subcommand(cli::stats::command())
+        .subcommand(cli::history::command())
+        .subcommand(cli::run::command())
         .get_matches();
+    let profile = args
+        .get_one::<String>("profile")
+        .cloned()
+        .unwrap_or(String::from("profile"));
+
+    // run a subcommand instead of the app, if one was given
+    match args.subcommand() {
+        Some(("export", sub)) => return cli::export::run(sub, &profile),
+        Some(("import", sub)) => return cli::import::run(sub, &profile),
+        Some(("merge", sub)) => return cli::merge::run(sub),
+        Some(("stats", sub)) => return cli::stats::run(sub, &profile),
+        Some(("history", sub)) => return cli::history::run(sub, &profile),
+        Some(("run", sub)) => {
+            std::panic::set_hook(Box::new(panic_handler));
+            return cli::run::run(sub, &profile);
+        }
+        Some(_) => unreachable!("unknown subcommand"),
+        None => {}
+    }
+
+    // restore a backup instead of running the app, if requested
+    if let Some(n) = args.get_one::<usize>("restore-backup") {
+        profile::Profile::restore_backup(&profile, *n)?;
+        println!(
+            "Restored '{profile}' from '{}'.",
+            profile::backup_path(&profile, *n)
+        );
+        return Ok(());
+    }
+
+    // read custom text before touching the terminal, so that bad paths are reported plainly
+    let custom_mode = args
+        .get_one::<String>("file")
+        .map(render::test::TestMode::custom)
+        .transpose()?;
+
+    // create menu, which can create and administer tests. this reads the profile, so it is done
+    // before touching the terminal too
+    let mut menu = if args.get_flag("no-profile") {
+        render::menu::MenuRenderer::new(None)?
+    } else {
+        render::menu::MenuRenderer::new(Some(profile))?
+    };
 
     // set panic hook in case anything goes wrong
     std::panic::set_hook(Box::new(panic_handler));
@@ -24,14 +94,11 @@ fn main() -> Result<(), std::io::Error> {
     // enable raw terminal
     enable_raw_mode().expect("failed to enable raw mode");
 
-    // render menu, which can create and administer tests
-    if args.get_flag("no-profile") {
-        render::menu::MenuRenderer::new(None).render()?;
-    } else if let Some(profile) = args.get_one::<String>("profile") {
-        render::menu::MenuRenderer::new(Some(profile.clone())).render()?;
-    } else {
-        render::menu::MenuRenderer::new(Some(String::from("profile"))).render()?;
+    // if a file was given, start with a test on its text, then render the menu
+    if let Some(mode) = custom_mode {
+        menu.run_test(&mode, None)?;
     }
+    menu.render()?;
 
     // disable raw terminal
     disable_raw_mode().expect("failed to disable raw mode");
@@ -1,6 +1,57 @@
-use crate::{config::Config, render::test::TestResult};
+use crate::{
+    config::Config,
+    render::test::{TestKind, TestResult},
+};
+use serde_cbor::Value;
 use serde_derive::{Deserialize, Serialize};
-use std::fs::File;
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::Path,
+};
+
+/// Version of the profile schema written by this version of WPM. Profiles without a version were
+/// written before the schema was versioned, and are version 0.
+const PROFILE_VERSION: u32 = 1;
+
+/// Number of backups kept of each profile. Backups are rotated every time a profile is saved, with
+/// `profile.1` being the most recent.
+pub const BACKUP_COUNT: usize = 3;
+
+/// Key the schema version is stored under, alongside the fields of the profile.
+const VERSION_KEY: &str = "version";
+
+/// Upgrades a profile from one schema version to the next.
+type Migration = fn(&mut BTreeMap<Value, Value>) -> Result<(), String>;
+
+/// Migrations between schema versions, where the migration at index `n` upgrades a profile from
+/// version `n` to `n + 1`.
+const MIGRATIONS: [Migration; PROFILE_VERSION as usize] = [v0_to_v1];
+
+/// Version 0 profiles cached a personal best holding gross WPM rather than net WPM. Cached
+/// statistics are dropped, so that they are rebuilt from history. They may also hold results
+/// recorded before raw WPM was tracked, whose WPMs were calculated from whole seconds. Those are
+/// recalculated, to stay comparable with newer results.
+fn v0_to_v1(profile: &mut BTreeMap<Value, Value>) -> Result<(), String> {
+    profile.remove(&Value::Text("stats".into()));
+    let Some(Value::Array(history)) = profile.get_mut(&Value::Text("history".into())) else {
+        return Ok(());
+    };
+    for test in history.iter_mut() {
+        let Value::Map(fields) = test else {
+            return Err("malformed test result".into());
+        };
+        if fields.contains_key(&Value::Text("raw_wpm".into())) {
+            continue;
+        }
+        let mut result: TestResult =
+            serde_cbor::value::from_value(test.clone()).map_err(|e| e.to_string())?;
+        result.calculate();
+        *test = serde_cbor::value::to_value(&result).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct ProfileStatistics {
@@ -10,8 +61,78 @@ pub struct ProfileStatistics {
     pub average_gross_wpm: f32,
     /// Average net WPM.
     pub average_net_wpm: f32,
-    /// Personal best gross WPM.
+    /// Average accuracy.
+    #[serde(default)]
+    pub average_accuracy: f32,
+    /// Personal best net WPM, across every kind of test.
     pub pb: f32,
+    /// Personal bests for each kind of test taken.
+    #[serde(default)]
+    pub pbs: Vec<PersonalBest>,
+}
+
+impl ProfileStatistics {
+    /// Computes statistics over the given tests.
+    pub fn from_tests<'a>(tests: impl IntoIterator<Item = &'a TestResult> + Clone) -> Self {
+        let mut stats = Self {
+            pbs: personal_bests(tests.clone()),
+            ..Default::default()
+        };
+
+        // average wpms and get pb
+        let (mut gross_sum, mut net_sum, mut accuracy_sum) = (0., 0., 0.);
+        for test in tests {
+            // add to averages
+            stats.total_tests += 1;
+            gross_sum += test.wpm.0;
+            net_sum += test.wpm.1;
+            accuracy_sum += test.accuracy;
+
+            // get pb from net, meaning including errors
+            if test.wpm.1 > stats.pb {
+                stats.pb = test.wpm.1;
+            }
+        }
+        if stats.total_tests > 0 {
+            stats.average_gross_wpm = gross_sum / stats.total_tests as f32;
+            stats.average_net_wpm = net_sum / stats.total_tests as f32;
+            stats.average_accuracy = accuracy_sum / stats.total_tests as f32;
+        }
+        stats
+    }
+}
+
+/// Best result on a single kind of test.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PersonalBest {
+    /// Kind of test.
+    pub kind: TestKind,
+    /// Best net WPM.
+    pub wpm: f32,
+    /// Accuracy of the test the best WPM was achieved on.
+    pub accuracy: f32,
+}
+
+/// Finds the personal best on each kind of test among the given tests, in the order each kind was
+/// first taken.
+pub fn personal_bests<'a>(tests: impl IntoIterator<Item = &'a TestResult>) -> Vec<PersonalBest> {
+    let mut pbs: Vec<PersonalBest> = vec![];
+    for test in tests {
+        let kind = test.kind();
+        match pbs.iter_mut().find(|pb| pb.kind == kind) {
+            Some(pb) if test.wpm.1 <= pb.wpm => {}
+            Some(pb) => {
+                pb.wpm = test.wpm.1;
+                pb.accuracy = test.accuracy;
+            }
+            None => pbs.push(PersonalBest {
+                kind,
+                wpm: test.wpm.1,
+                accuracy: test.accuracy,
+            }),
+        }
+    }
+    pbs
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -19,6 +140,7 @@ pub struct Profile {
     /// Test history.
     history: Vec<TestResult>,
     /// Statistics.
+    #[serde(default)]
     stats: ProfileStatistics,
     /// Current configuration.
     config: Config,
@@ -45,6 +167,11 @@ impl Profile {
         &self.history
     }
 
+    /// Get the personal best on the given kind of test, if it has been taken.
+    pub fn get_pb(&self, kind: &TestKind) -> Option<&PersonalBest> {
+        self.stats.pbs.iter().find(|pb| pb.kind == *kind)
+    }
+
     /// Get the last `n` test records, where `n` is specified by the current configuration.
     pub fn get_recent(&self) -> Vec<&TestResult> {
         let n = self.config.get_int("recent test count") as usize;
@@ -53,30 +180,7 @@ impl Profile {
 
     /// Update this profile's statistics.
     pub fn update_stats(&mut self) {
-        // total tests
-        self.stats.total_tests = self.history.len() as u64;
-
-        // average wpms and get pb
-        if self.stats.total_tests == 0 {
-            self.stats.average_gross_wpm = 0.;
-            self.stats.average_net_wpm = 0.;
-            self.stats.pb = 0.;
-        } else {
-            let (mut gross_sum, mut net_sum, mut max_wpm) = (0., 0., 0.);
-            for test in &self.history {
-                // add to averages
-                gross_sum += test.wpm.0;
-                net_sum += test.wpm.1;
-
-                // get pb from net, meaning including errors
-                if test.wpm.1 > max_wpm {
-                    max_wpm = test.wpm.0;
-                }
-            }
-            self.stats.average_gross_wpm = gross_sum / self.stats.total_tests as f32;
-            self.stats.average_net_wpm = net_sum / self.stats.total_tests as f32;
-            self.stats.pb = max_wpm;
-        }
+        self.stats = ProfileStatistics::from_tests(&self.history);
     }
 
     /// Records the given test result.
@@ -84,18 +188,370 @@ impl Profile {
         self.history.push(test);
     }
 
-    /// Save `&self` to the provided file path.
+    /// Removes the test result at `idx` from history, and updates statistics to match.
+    pub fn remove(&mut self, idx: usize) -> TestResult {
+        let test = self.history.remove(idx);
+        self.update_stats();
+        test
+    }
+
+    /// Merges the history of `other` into this profile. Tests found in both are kept once, and
+    /// history is ordered by when tests were taken, with untimestamped tests first. This profile's
+    /// configuration takes precedence over `other`'s.
+    pub fn merge(mut self, other: Profile) -> Self {
+        for test in other.history {
+            if !self.history.iter().any(|own| own.is_same_test(&test)) {
+                self.history.push(test);
+            }
+        }
+        self.sort_history();
+        self.update_stats();
+        self
+    }
+
+    /// Orders history by when tests were taken, with untimestamped tests first. Tests taken at
+    /// the same time keep their order.
+    pub fn sort_history(&mut self) {
+        self.history.sort_by_key(|test| test.timestamp);
+    }
+
+    /// Save `&self` to the provided file path, tagged with the current schema version. The profile
+    /// is written to a temporary file and renamed over the old one, so a failed write never leaves
+    /// a partial profile behind. The old profile is kept as the most recent backup.
     pub fn write_to(&self, file: impl Into<String>) -> Result<(), std::io::Error> {
         let file = file.into();
-        let file = File::create(file)?;
-        serde_cbor::to_writer(file, &self).expect("Failed to write to CBOR writer.");
-        Ok(())
+        let mut value = serde_cbor::value::to_value(self).map_err(std::io::Error::other)?;
+        if let Value::Map(map) = &mut value {
+            map.insert(
+                Value::Text(VERSION_KEY.into()),
+                Value::Integer(PROFILE_VERSION.into()),
+            );
+        }
+        let bytes = serde_cbor::to_vec(&value).map_err(std::io::Error::other)?;
+
+        // rotate backups, dropping the oldest
+        if Path::new(&file).exists() {
+            for n in (1..BACKUP_COUNT).rev() {
+                let backup = backup_path(&file, n);
+                if Path::new(&backup).exists() {
+                    fs::rename(&backup, backup_path(&file, n + 1))?;
+                }
+            }
+            fs::copy(&file, backup_path(&file, 1))?;
+        }
+
+        // write the new profile into place
+        write_atomic(&file, &bytes)
     }
 
-    /// Read the profile at the provided file path.
-    pub fn read_from(file: impl Into<String>) -> Result<Self, std::io::Error> {
+    /// Restores the profile at the provided file path from its `n`th most recent backup. The
+    /// backup must be readable. The profile being replaced, if any, is kept alongside it with a
+    /// `.before-restore` suffix.
+    pub fn restore_backup(file: impl Into<String>, n: usize) -> Result<(), std::io::Error> {
         let file = file.into();
-        let file = File::open(file)?;
-        Ok(serde_cbor::from_reader(file).expect("Failed to read from CBOR reader."))
+        let backup = backup_path(&file, n);
+        match Self::read_from(&backup) {
+            Ok(_) => {}
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                // name the backups that can be restored instead
+                let available = (1..=BACKUP_COUNT)
+                    .map(|n| backup_path(&file, n))
+                    .filter(|backup| Path::new(backup).exists())
+                    .map(|backup| format!("'{backup}'"))
+                    .collect::<Vec<String>>();
+                let available = if available.is_empty() {
+                    String::from("no backups exist")
+                } else {
+                    format!("available backups: {}", available.join(", "))
+                };
+                return Err(std::io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("no backup found at '{backup}' ({available})"),
+                ));
+            }
+            Err(e) => return Err(e),
+        }
+        if Path::new(&file).exists() {
+            fs::copy(&file, format!("{file}.before-restore"))?;
+        }
+        write_atomic(&file, &fs::read(&backup)?)
+    }
+
+    /// Read the profile at the provided file path, or start a fresh one if there is no profile
+    /// there yet.
+    pub fn read_or_default(file: impl Into<String>) -> Result<Self, std::io::Error> {
+        match Self::read_from(file) {
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            result => result,
+        }
+    }
+
+    /// Read the profile at the provided file path, migrating it from older schema versions. Fails
+    /// if the file cannot be parsed, or was written by a newer version of WPM.
+    pub fn read_from(file: impl Into<String>) -> Result<Self, std::io::Error> {
+        let path = file.into();
+        let invalid = |reason: String| {
+            std::io::Error::new(
+                ErrorKind::InvalidData,
+                format!("profile '{path}' could not be read: {reason}"),
+            )
+        };
+
+        // parse the file, without assuming any particular schema
+        let file = File::open(&path)?;
+        let value: Value = serde_cbor::from_reader(file).map_err(|e| invalid(e.to_string()))?;
+        let Value::Map(mut map) = value else {
+            return Err(invalid("not a profile".into()));
+        };
+
+        // upgrade the profile to the current schema version
+        let version = match map.remove(&Value::Text(VERSION_KEY.into())) {
+            Some(Value::Integer(version)) if version >= 0 => version,
+            Some(_) => return Err(invalid("malformed schema version".into())),
+            None => 0,
+        };
+        if version > PROFILE_VERSION as i128 {
+            return Err(invalid(format!(
+                "it was written by a newer version of wpm (schema v{version}, this version reads \
+                 up to v{PROFILE_VERSION})"
+            )));
+        }
+        for migration in &MIGRATIONS[version as usize..] {
+            migration(&mut map).map_err(&invalid)?;
+        }
+
+        // read the upgraded profile
+        let mut profile: Self =
+            serde_cbor::value::from_value(Value::Map(map)).map_err(|e| invalid(e.to_string()))?;
+        profile.config.fill_defaults();
+        profile.update_stats();
+        Ok(profile)
+    }
+}
+
+/// Gets the path of the `n`th most recent backup of the profile at `file`.
+pub fn backup_path(file: &str, n: usize) -> String {
+    format!("{file}.{n}")
+}
+
+/// Writes `bytes` to `file` atomically, by writing and syncing a temporary file, then renaming it
+/// over `file`.
+fn write_atomic(file: &str, bytes: &[u8]) -> Result<(), std::io::Error> {
+    let temp = format!("{file}.tmp");
+    let mut handle = File::create(&temp)?;
+    handle.write_all(bytes)?;
+    handle.sync_all()?;
+    fs::rename(&temp, file)?;
+
+    // sync the directory too, so that the rename itself is durable. not every platform allows
+    // opening directories, so this is best-effort
+    let dir = Path::new(file)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    if let Ok(dir) = File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigValue;
+    use crate::render::{
+        test::{Modifiers, TestMode},
+        wordlist::Wordlist,
+    };
+    use std::time::Duration;
+
+    /// Creates a test result with the given number of hits and misses, taken over a minute.
+    fn test_result(mode: TestMode, hits: usize, misses: usize) -> TestResult {
+        TestResult::builder(mode, Wordlist::English1k)
+            .counts(hits, misses)
+            .time(Duration::from_secs(60))
+            .build()
+    }
+
+    #[test]
+    fn pbs_are_tracked_per_kind() {
+        let mut profile = Profile::default();
+        profile.record(test_result(TestMode::Words(10), 300, 0));
+        profile.record(test_result(TestMode::Words(10), 400, 50));
+        profile.record(test_result(TestMode::Words(25), 200, 0));
+        profile.update_stats();
+
+        // the global pb is the best net wpm
+        assert_eq!(profile.get_stats().pb, 60.);
+
+        // each kind of test has its own pb
+        let kind = |mode| TestKind::new(mode, Wordlist::English1k, Modifiers::default());
+        assert_eq!(profile.get_pb(&kind(TestMode::Words(10))).unwrap().wpm, 60.);
+        assert_eq!(profile.get_pb(&kind(TestMode::Words(25))).unwrap().wpm, 40.);
+        assert!(profile.get_pb(&kind(TestMode::Words(50))).is_none());
+    }
+
+    #[test]
+    fn merges_are_deduplicated_and_ordered() {
+        let timestamped = |hits, timestamp| {
+            let mut test = test_result(TestMode::Words(10), hits, 0);
+            test.timestamp = Some(timestamp);
+            test
+        };
+        let mut a = Profile::default();
+        a.record(timestamped(300, 30));
+        a.record(timestamped(200, 10));
+        a.get_config_mut()
+            .set("include punctuation", ConfigValue::Bool(true));
+        let mut b = Profile::default();
+        b.record(test_result(TestMode::Words(10), 100, 0));
+        b.record(timestamped(400, 20));
+        b.record(timestamped(300, 30));
+
+        let merged = a.merge(b);
+        let hits = merged
+            .get_history()
+            .iter()
+            .map(|test| test.hits)
+            .collect::<Vec<usize>>();
+        assert_eq!(hits, vec![100, 200, 400, 300]);
+        assert_eq!(merged.get_stats().total_tests, 4);
+        assert!(merged.get_config().get_bool("include punctuation"));
+    }
+
+    /// Gets a path in the temporary directory that is unique to the calling test.
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("wpm-{}-{name}", std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let path = temp_path("round-trip");
+        let mut profile = Profile::default();
+        profile.record(test_result(TestMode::Words(10), 300, 0));
+        profile.update_stats();
+        profile.write_to(&path).unwrap();
+
+        let read = Profile::read_from(&path).unwrap();
+        assert_eq!(read.get_history().len(), 1);
+        assert_eq!(read.get_stats().pb, 60.);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unversioned_profiles_are_migrated() {
+        // a version 0 profile, with a gross pb cached
+        let path = temp_path("unversioned");
+        let mut profile = Profile::default();
+        profile.record(test_result(TestMode::Words(10), 400, 50));
+        profile.update_stats();
+        profile.stats.pb = 90.;
+        serde_cbor::to_writer(File::create(&path).unwrap(), &profile).unwrap();
+
+        let read = Profile::read_from(&path).unwrap();
+        assert_eq!(read.get_stats().pb, 40.);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn legacy_results_are_recalculated_once() {
+        // a version 0 profile, with a result recorded before raw wpm was tracked
+        let path = temp_path("legacy");
+        let mut profile = Profile::default();
+        let mut test = test_result(TestMode::Words(10), 400, 50);
+        test.wpm = (10., 5.);
+        profile.record(test);
+        let mut value = serde_cbor::value::to_value(&profile).unwrap();
+        if let Value::Map(map) = &mut value {
+            if let Some(Value::Array(history)) = map.get_mut(&Value::Text("history".into())) {
+                if let Value::Map(test) = &mut history[0] {
+                    test.remove(&Value::Text("raw_wpm".into()));
+                }
+            }
+        }
+        serde_cbor::to_writer(File::create(&path).unwrap(), &value).unwrap();
+        let mut read = Profile::read_from(&path).unwrap();
+        assert_eq!(read.get_history()[0].wpm, (90., 40.));
+
+        // once versioned, results are left alone, even without a raw wpm
+        read.history[0].wpm = (10., 5.);
+        read.history[0].raw_wpm = 0.;
+        read.write_to(&path).unwrap();
+        let read = Profile::read_from(&path).unwrap();
+        assert_eq!(read.get_history()[0].wpm, (10., 5.));
+        std::fs::remove_file(backup_path(&path, 1)).unwrap();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unreadable_profiles_are_refused() {
+        // garbage
+        let path = temp_path("garbage");
+        std::fs::write(&path, b"not a profile").unwrap();
+        let err = Profile::read_from(&path).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        // a profile from the future
+        let mut map = BTreeMap::new();
+        map.insert(
+            Value::Text(VERSION_KEY.into()),
+            Value::Integer(PROFILE_VERSION as i128 + 1),
+        );
+        serde_cbor::to_writer(File::create(&path).unwrap(), &Value::Map(map)).unwrap();
+        let err = Profile::read_from(&path).err().unwrap();
+        assert!(err.to_string().contains("newer version"));
+
+        // a profile with a negative version
+        let mut map = BTreeMap::new();
+        map.insert(Value::Text(VERSION_KEY.into()), Value::Integer(-1));
+        serde_cbor::to_writer(File::create(&path).unwrap(), &Value::Map(map)).unwrap();
+        let err = Profile::read_from(&path).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().contains("malformed schema version"));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn backups_rotate_and_restore() {
+        let path = temp_path("backups");
+        let mut profile = Profile::default();
+        for i in 0..=BACKUP_COUNT {
+            profile.record(test_result(TestMode::Words(10), 100 * (i + 1), 0));
+            profile.write_to(&path).unwrap();
+        }
+
+        // every save but the last is kept, up to the backup count
+        assert_eq!(Profile::read_from(&path).unwrap().get_history().len(), 4);
+        for n in 1..=BACKUP_COUNT {
+            let backup = Profile::read_from(backup_path(&path, n)).unwrap();
+            assert_eq!(backup.get_history().len(), 4 - n);
+        }
+        assert!(!Path::new(&backup_path(&path, BACKUP_COUNT + 1)).exists());
+
+        // restoring a missing backup names the ones that exist
+        let err = Profile::restore_backup(&path, BACKUP_COUNT + 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert!(err
+            .to_string()
+            .contains(&format!("'{}'", backup_path(&path, BACKUP_COUNT))));
+
+        // restoring replaces the profile, and keeps the one it replaced
+        Profile::restore_backup(&path, 2).unwrap();
+        assert_eq!(Profile::read_from(&path).unwrap().get_history().len(), 2);
+        let replaced = format!("{path}.before-restore");
+        assert_eq!(
+            Profile::read_from(&replaced).unwrap().get_history().len(),
+            4
+        );
+
+        // clean up
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&replaced).unwrap();
+        for n in 1..=BACKUP_COUNT {
+            fs::remove_file(backup_path(&path, n)).unwrap();
+        }
     }
 }
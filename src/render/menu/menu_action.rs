@@ -8,6 +8,8 @@ pub enum MenuAction {
         mode: TestMode,
         wordlist: Option<Wordlist>,
     },
+    /// Prompts for a file path, and executes a test on its text.
+    CustomTest,
     /// Opens profile view.
     Profile,
     /// Toggles a boolean config value.
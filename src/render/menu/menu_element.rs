@@ -1,6 +1,9 @@
 use super::*;
 use std::rc::Rc;
 
+/// Update callback for a `MenuElement`, executed before each render.
+pub type UpdateCallback = Rc<dyn Fn(&Profile, &mut MenuElement)>;
+
 /// Represents menu options and submenus.
 #[derive(Clone)]
 pub struct MenuElement {
@@ -11,7 +14,7 @@ pub struct MenuElement {
     /// Element update callback. Used to update data if needed. More arguments could be used if
     /// required for further functionality than recent plays, which is what this feature was
     /// intended for.
-    update_cb: Option<Rc<dyn Fn(&Profile, &mut Self)>>,
+    update_cb: Option<UpdateCallback>,
     /// Element action, if this is an action.
     action: MenuAction,
 }
@@ -22,7 +25,7 @@ impl MenuElement {
     pub fn new_menu_cb(
         label: impl Into<String>,
         subitems: Vec<MenuElement>,
-        update_cb: Option<Rc<dyn Fn(&Profile, &mut Self)>>,
+        update_cb: Option<UpdateCallback>,
     ) -> Self {
         Self {
             label: label.into(),
@@ -8,8 +8,12 @@ use std::{
     time::Duration,
 };
 
-use super::{test::*, util::*, wordlist::*};
-use crate::{config::ConfigValue, profile::Profile, render::stats::StatsRenderer};
+use super::{quote::QuoteLength, snippet::CodeLanguage, test::*, util::*, wordlist::*};
+use crate::{
+    config::ConfigValue,
+    profile::Profile,
+    render::{results::ResultsRenderer, stats::StatsRenderer},
+};
 use crossterm::{
     cursor::{Hide, MoveRight, MoveTo, MoveToNextLine, MoveUp, Show},
     event::{poll, read, Event, KeyCode, KeyEvent},
@@ -40,22 +44,30 @@ pub struct MenuRenderer {
 }
 
 impl MenuRenderer {
-    pub fn new(profile_path: Option<String>) -> Self {
+    /// Creates a menu over the profile at the given path. Fails if the profile exists but cannot
+    /// be read, rather than starting over and overwriting it on exit.
+    pub fn new(profile_path: Option<String>) -> Result<Self, std::io::Error> {
         // if no profile was specified, the user does not want to save
         let save = profile_path.is_some();
 
-        // load stored profile, or default if applicable
+        // load stored profile, or default if there isn't one yet
         let profile_path = profile_path.unwrap_or(String::from("profile"));
-        let profile = if !save {
+        let mut profile = if !save {
             Profile::default()
         } else {
-            Profile::read_from(&profile_path).unwrap_or_default()
-        }
-        .into();
+            Profile::read_or_default(&profile_path)?
+        };
+
+        // wordlists on disk may have changed since the profile was saved, so refresh them
+        profile.get_config_mut().set_options(
+            "wordlist",
+            Wordlist::all().iter().map(Wordlist::name).collect(),
+        );
+        let profile = profile.into();
 
         // make menu items
         use TestMode::*;
-        Self {
+        Ok(Self {
             save,
             cursor: vec![0],
             profile,
@@ -102,22 +114,77 @@ impl MenuRenderer {
                                     ),
                                 ],
                             ),
+                            // adaptive
+                            MenuElement::new_menu(
+                                "adaptive",
+                                vec![
+                                    MenuElement::new_test("adaptive 25", Adaptive(25), None),
+                                    MenuElement::new_test("adaptive 50", Adaptive(50), None),
+                                    MenuElement::new_test("adaptive 100", Adaptive(100), None),
+                                ],
+                            ),
+                            // quote
+                            MenuElement::new_menu(
+                                "quote",
+                                vec![
+                                    MenuElement::new_test(
+                                        "quote short",
+                                        Quote(QuoteLength::Short),
+                                        None,
+                                    ),
+                                    MenuElement::new_test(
+                                        "quote medium",
+                                        Quote(QuoteLength::Medium),
+                                        None,
+                                    ),
+                                    MenuElement::new_test(
+                                        "quote long",
+                                        Quote(QuoteLength::Long),
+                                        None,
+                                    ),
+                                    MenuElement::new_test(
+                                        "quote any",
+                                        Quote(QuoteLength::Any),
+                                        None,
+                                    ),
+                                ],
+                            ),
+                            // code
+                            MenuElement::new_menu(
+                                "code",
+                                CodeLanguage::iter()
+                                    .map(|language| {
+                                        MenuElement::new_test(
+                                            format!("code {language}"),
+                                            Code(language),
+                                            None,
+                                        )
+                                    })
+                                    .collect(),
+                            ),
+                            // custom text
+                            MenuElement::new_action("custom text", MenuAction::CustomTest),
                         ],
                         // recents updater
                         Some(Rc::new(|profile, element| {
                             // remove old subitems
                             let subitems = element.subitems_mut().unwrap(); // safe unwrap
-                            subitems.retain(|v| v.subitems().is_some());
+                            subitems.retain(|v| !matches!(v.action(), MenuAction::Test { .. }));
 
                             // if enabled, add recents
                             if profile.get_config().get_bool("show recent tests") {
                                 // get recent plays
                                 let mut recents = vec![];
                                 for entry in profile.get_recent() {
+                                    let label = if entry.mode.uses_wordlist() {
+                                        format!("󰕍 {} ({})", entry.mode, entry.wordlist.name())
+                                    } else {
+                                        format!("󰕍 {}", entry.mode)
+                                    };
                                     recents.push(MenuElement::new_test(
-                                        format!("󰕍 {} ({:?})", entry.mode, entry.wordlist),
+                                        label,
                                         entry.mode.clone(),
-                                        Some(entry.wordlist),
+                                        Some(entry.wordlist.clone()),
                                     ));
                                 }
 
@@ -144,19 +211,11 @@ impl MenuRenderer {
                                     // adding colors to config values here count as part of the
                                     // text width, which makes the menu blow up in size
                                     Bool(_) => settings.push(MenuElement::new_action(
-                                        format!(
-                                            "{} ({})",
-                                            key,
-                                            profile.get_config().get(key).to_string()
-                                        ),
+                                        format!("{} ({})", key, profile.get_config().get(key)),
                                         MenuAction::CfgToggle(key.clone()),
                                     )),
                                     Integer { .. } => settings.push(MenuElement::new_action(
-                                        format!(
-                                            "{} ({})",
-                                            key,
-                                            profile.get_config().get(key).to_string()
-                                        ),
+                                        format!("{} ({})", key, profile.get_config().get(key)),
                                         MenuAction::CfgIncrement(key.clone()),
                                     )),
                                     Select { options, selected } => {
@@ -179,11 +238,7 @@ impl MenuRenderer {
 
                                         // create new menu to hold elements
                                         settings.push(MenuElement::new_menu(
-                                            format!(
-                                                "{} ({})",
-                                                key,
-                                                profile.get_config().get(key).to_string()
-                                            ),
+                                            format!("{} ({})", key, profile.get_config().get(key)),
                                             dropdown_items,
                                         ))
                                     }
@@ -194,7 +249,7 @@ impl MenuRenderer {
                     ),
                 ],
             ),
-        }
+        })
     }
 
     /// Renders the menu util exited or a test is started.
@@ -255,6 +310,7 @@ impl MenuRenderer {
                 );
             let mut this_max_x: usize = 0; // the longest line of this render cycle
             let mut last_max_x: usize = 0; // the longest line of the last render cycle
+            let mut menu_x: usize = 0; // the left edge of the current menu
 
             // render main menu stack
             for (depth, menu) in menus.iter().enumerate() {
@@ -311,6 +367,9 @@ impl MenuRenderer {
                         lns += 1;
                     }
                 }
+                if depth == menus.len() - 1 {
+                    menu_x = MARGIN + last_max_x;
+                }
                 last_max_x = last_max_x + this_max_x + GAP;
                 this_max_x = 0;
 
@@ -319,6 +378,31 @@ impl MenuRenderer {
                     queue!(stdout, MoveUp(lns))?;
                 }
             }
+
+            // show the personal best on the selected test, if it has been taken
+            let selected = menus
+                .last()
+                .unwrap()
+                .subitems()
+                .unwrap()
+                .get(*cursor.last().unwrap());
+            if let Some(MenuAction::Test { mode, wordlist }) = selected.map(MenuElement::action) {
+                let (wordlist, modifiers) = self.test_options(mode, wordlist.clone());
+                let kind = TestKind::new(mode.clone(), wordlist, modifiers);
+                if let Some(pb) = self.profile.borrow().get_pb(&kind) {
+                    queue!(
+                        stdout,
+                        MoveToNextLine(1),
+                        MoveRight(menu_x as u16),
+                        Print(format!(
+                            "{} pb {:.1}wpm ({:.1}%)",
+                            "".yellow(),
+                            pb.wpm,
+                            pb.accuracy
+                        )),
+                    )?;
+                }
+            }
             self.cursor = cursor;
 
             // render errors
@@ -346,16 +430,17 @@ impl MenuRenderer {
                             // otherwise, just exit the program itself
                             if self.cursor.len() > 1 {
                                 self.cursor.pop();
+                                Ok(())
+                            } else if !self.save {
+                                break;
                             } else {
-                                if self.save {
-                                    self.profile
-                                        .borrow_mut()
-                                        .write_to(self.profile_path.clone())
-                                        .expect("Failed to write profile.");
+                                // if the profile can't be saved, stay open and show the error
+                                // rather than losing this session
+                                match self.profile.borrow().write_to(self.profile_path.clone()) {
+                                    Ok(()) => break,
+                                    Err(e) => Err(e),
                                 }
-                                break;
                             }
-                            Ok(())
                         }
                         _ => self.handle_key(key),
                     },
@@ -408,68 +493,20 @@ impl MenuRenderer {
         {
             use MenuAction::*;
             match &e.action() {
-                Test { mode, wordlist } => {
-                    // if the wordlist is present, use it. otherwise, use the one in the
-                    // configuration file
-                    let wordlist = wordlist.to_owned().unwrap_or(
-                        self.profile
-                            .borrow()
-                            .get_config()
-                            .get_select("wordlist")
-                            .into(),
-                    );
-
-                    // execute test renderer
-                    let content = wordlist.as_content();
-                    let tokens: Vec<&str> = str_to_tokens(content.as_str());
-                    let phrase = match mode {
-                        TestMode::Words(length) => tokens_to_phrase(*length, &tokens),
-                        TestMode::Time(_) => tokens_to_phrase(100, &tokens),
-                    };
-                    let mut profile = self.profile.borrow_mut();
-                    let result = TestRenderer::new(wordlist, phrase, mode.to_owned())
-                        .render(profile.get_config())?;
-
-                    // if user abandoned test, we're done here
-                    if result.is_none() {
-                        return Ok(());
+                Test { mode, wordlist } => self.run_test(mode, wordlist.clone())?,
+                CustomTest => {
+                    let path = prompt(&mut stdout(), "path to text file (esc to cancel)")?;
+                    if let Some(path) = path {
+                        self.run_test(&TestMode::custom(path.trim())?, Option::None)?;
                     }
-
-                    // temporarily show results before continuing
-                    let result = result.unwrap(); // safety above
-                    let mut stdout = stdout();
-                    queue!(
-                        // basic initial stats
-                        stdout,
-                        Print(format!("GROSS: {:.2} wpm", result.wpm.0)),
-                        MoveToNextLine(1),
-                        Print(format!(
-                            "NET:   {:.2}wpm ({}X)",
-                            result.wpm.1, result.misses,
-                        )),
-                        MoveToNextLine(1),
-                    )?;
-                    if result.wpm.1 > profile.get_stats().pb {
-                        queue!(
-                            stdout,
-                            Print(format!("{} {}", "".yellow(), "new pb!".italic())),
-                            MoveToNextLine(1),
-                        )?;
+                }
+                Profile => {
+                    // the user may choose to drill an n-gram from their statistics
+                    let drill = StatsRenderer::new(&mut self.profile.borrow_mut()).render()?;
+                    if let Some(mode) = drill {
+                        self.run_test(&mode, Option::None)?;
                     }
-                    queue!(
-                        // continue message
-                        stdout,
-                        MoveToNextLine(1),
-                        Print("Press enter to continue.".italic())
-                    )?;
-                    stdout.flush()?;
-                    pause(Some(Duration::from_secs(10)));
-
-                    // otherwise, add test record to profile
-                    profile.record(result);
-                    profile.update_stats();
                 }
-                Profile => StatsRenderer::new(&self.profile.borrow()).render()?,
                 CfgToggle(key) => {
                     let mut profile = self.profile.borrow_mut();
                     let cfg = profile.get_config_mut();
@@ -508,6 +545,62 @@ impl MenuRenderer {
         Ok(())
     }
 
+    /// Executes a test with the given mode, then shows and records its results. If `wordlist` is
+    /// `None`, the one in the configuration is used.
+    pub fn run_test(
+        &self,
+        mode: &TestMode,
+        wordlist: Option<Wordlist>,
+    ) -> Result<(), std::io::Error> {
+        let (wordlist, modifiers) = self.test_options(mode, wordlist);
+
+        // execute test renderer. custom tests are re-identified by the text actually typed, in case
+        // their file has changed since they were last run
+        let (phrase, source) =
+            mode.phrase(&wordlist, modifiers, self.profile.borrow().get_history())?;
+        let mode = match mode {
+            TestMode::Custom { name, .. } => TestMode::Custom {
+                name: name.clone(),
+                hash: hash_str(&phrase),
+            },
+            mode => mode.clone(),
+        };
+        let mut profile = self.profile.borrow_mut();
+        let result = TestRenderer::new(wordlist, phrase, mode).render(profile.get_config())?;
+
+        // if user abandoned test, we're done here
+        if result.is_none() {
+            return Ok(());
+        }
+
+        // show results before continuing
+        let mut result = result.unwrap(); // safety above
+        result.source = source;
+        result.modifiers = modifiers;
+        ResultsRenderer::new(&result, profile.get_history()).render()?;
+
+        // otherwise, add test record to profile
+        profile.record(result);
+        profile.update_stats();
+
+        Ok(())
+    }
+
+    /// Gets the wordlist and modifiers a test in the given mode would be taken with. If the
+    /// wordlist is present, it is used. Otherwise, the one in the configuration file is.
+    fn test_options(&self, mode: &TestMode, wordlist: Option<Wordlist>) -> (Wordlist, Modifiers) {
+        let profile = self.profile.borrow();
+        let wordlist = wordlist.unwrap_or(profile.get_config().get_select("wordlist").into());
+
+        // modifiers only apply to phrases generated from wordlists
+        let modifiers = if mode.uses_wordlist() {
+            Modifiers::from_config(profile.get_config())
+        } else {
+            Modifiers::default()
+        };
+        (wordlist, modifiers)
+    }
+
     /// Get menus from cursor position.
     fn get_menus_from_cursor(&self) -> Vec<&MenuElement> {
         // create vec and add the first menu, the root one
@@ -545,7 +638,7 @@ mod tests {
 
     /// Creates a test MenuRenderer that is configured to no-profile mode
     fn create_test_menu_renderer() -> MenuRenderer {
-        MenuRenderer::new(None)
+        MenuRenderer::new(None).unwrap()
     }
 
     #[test]
@@ -1,6 +1,15 @@
 /// Menu renderer.
 pub mod menu;
 
+/// Quote corpus and related functions.
+pub mod quote;
+
+/// Code snippet corpora and related functions.
+pub mod snippet;
+
+/// Test results renderer.
+pub mod results;
+
 /// Profile statistics renderer.
 pub mod stats;
 
@@ -0,0 +1,109 @@
+use flate2::bufread::GzDecoder;
+use rand::prelude::IndexedRandom;
+use serde_derive::{Deserialize, Serialize};
+use std::{fmt::Display, io::Read};
+
+/// Compressed quote corpus. Each line holds a quote and its source, separated by a tab.
+const QUOTES: &[u8] = include_bytes!("../../wordlist/quotes_eng.txt.gz");
+
+/// Length bucket of a quote, measured in characters.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum QuoteLength {
+    /// Up to 100 characters.
+    Short,
+    /// Between 101 and 300 characters.
+    Medium,
+    /// More than 300 characters.
+    Long,
+    /// Any length.
+    Any,
+}
+
+impl QuoteLength {
+    /// Returns true if a quote of `len` characters falls within this bucket.
+    pub fn contains(&self, len: usize) -> bool {
+        use QuoteLength::*;
+        match self {
+            Short => len <= 100,
+            Medium => (101..=300).contains(&len),
+            Long => len > 300,
+            Any => true,
+        }
+    }
+}
+
+impl Display for QuoteLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use QuoteLength::*;
+        match self {
+            Short => write!(f, "short"),
+            Medium => write!(f, "medium"),
+            Long => write!(f, "long"),
+            Any => write!(f, "any"),
+        }
+    }
+}
+
+/// A single quote, with attribution.
+pub struct Quote {
+    /// Quote content.
+    pub text: String,
+    /// Where the quote came from.
+    pub source: String,
+}
+
+impl Quote {
+    /// Decompresses and parses the entire quote corpus.
+    pub fn all() -> Vec<Self> {
+        let mut buf = String::new();
+        GzDecoder::new(QUOTES)
+            .read_to_string(&mut buf)
+            .expect("Failed to decompress quote corpus.");
+        buf.lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(text, source)| Self {
+                text: text.into(),
+                source: source.into(),
+            })
+            .collect()
+    }
+
+    /// Selects a random quote within the given length bucket.
+    pub fn random(length: QuoteLength) -> Self {
+        let quotes = Self::all()
+            .into_iter()
+            .filter(|quote| length.contains(quote.text.len()))
+            .collect::<Vec<Self>>();
+        let quote = quotes
+            .choose(&mut rand::rng())
+            .unwrap_or_else(|| panic!("no quotes of length '{length}' found"));
+        Self {
+            text: quote.text.clone(),
+            source: quote.source.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_bucket_has_quotes() {
+        use QuoteLength::*;
+        let quotes = Quote::all();
+        for length in [Short, Medium, Long, Any] {
+            assert!(quotes.iter().any(|q| length.contains(q.text.len())));
+        }
+    }
+
+    #[test]
+    fn quotes_are_typeable() {
+        // curly quotes, dashes and the like can't be typed on most keyboards, so the bundled
+        // corpus sticks to plain ascii
+        for quote in Quote::all() {
+            assert!(quote.text.is_ascii(), "non-ascii quote: {}", quote.text);
+            assert!(!quote.source.is_empty());
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use super::{test::TestResult, util::*};
+use crossterm::{
+    cursor::{MoveTo, MoveToNextLine},
+    queue,
+    style::{Print, Stylize},
+    terminal::{disable_raw_mode, enable_raw_mode, size},
+};
+use rgb::RGB;
+use std::io::{stdout, Write};
+use textplots::*;
+
+/// Number of recent tests of the same kind averaged for comparison.
+const ROLLING_AVERAGE: usize = 10;
+
+/// Renders the results of a single test.
+pub struct ResultsRenderer<'a> {
+    /// Result to display.
+    result: &'a TestResult,
+    /// Tests taken before the result, which it is compared against.
+    previous: &'a [TestResult],
+}
+
+impl<'a> ResultsRenderer<'a> {
+    pub fn new(result: &'a TestResult, previous: &'a [TestResult]) -> Self {
+        Self { result, previous }
+    }
+
+    /// Renders the results screen until the user presses enter or ESC.
+    pub fn render(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let result = self.result;
+        clear(&mut stdout);
+
+        // header
+        queue!(
+            stdout,
+            MoveTo(0, 0),
+            Print(format!(" {} ", result.mode).black().on_grey()),
+        )?;
+        if result.mode.uses_wordlist() {
+            queue!(
+                stdout,
+                Print(
+                    format!(" {} ", result.wordlist.name())
+                        .grey()
+                        .on_dark_grey()
+                )
+            )?;
+        }
+        if !result.modifiers.is_empty() {
+            queue!(
+                stdout,
+                Print(format!(" with {}", result.modifiers).dark_grey())
+            )?;
+        }
+        queue!(stdout, MoveToNextLine(2))?;
+
+        // per-second wpm chart
+        self.render_chart()?;
+
+        // speed and accuracy
+        let consistency = match result.consistency() {
+            Some(cv) => format!("{:.0}% (cv {:.2})", ((1. - cv) * 100.).max(0.), cv),
+            None => String::from("-"),
+        };
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(format!("|{:^16}| {:.2}wpm", "net", result.wpm.1).bold()),
+            MoveToNextLine(1),
+            Print(format!("|{:^16}| {:.2}wpm", "raw", result.raw_wpm)),
+            MoveToNextLine(1),
+            Print(format!("|{:^16}| {:.1}%", "accuracy", result.accuracy)),
+            MoveToNextLine(1),
+            Print(format!("|{:^16}| {}", "consistency", consistency)),
+            MoveToNextLine(1),
+        )?;
+        if let Some(fatigue) = result.fatigue() {
+            let fatigue = format!("{:+.0}% from start to end", fatigue * 100.);
+            queue!(
+                stdout,
+                Print(format!("|{:^16}| {}", "speed change", fatigue)),
+                MoveToNextLine(1),
+            )?;
+        }
+        queue!(
+            stdout,
+            Print(format!(
+                "|{:^16}| {:.2}s",
+                "time",
+                result.time.as_secs_f32()
+            )),
+            MoveToNextLine(1),
+        )?;
+
+        // characters, as correct/incorrect/extra/missed
+        let counts = result.char_counts();
+        queue!(
+            stdout,
+            Print(format!(
+                "|{:^16}| {}/{}/{}/{} {}",
+                "characters",
+                counts.correct.to_string().green(),
+                counts.incorrect.to_string().red(),
+                counts.extra.to_string().yellow(),
+                counts.missed.to_string().dark_grey(),
+                "(correct/incorrect/extra/missed)".dark_grey().italic(),
+            )),
+            MoveToNextLine(2),
+        )?;
+
+        // comparison against earlier tests of the same kind
+        self.render_comparison()?;
+
+        // mistyped words
+        let words = result.mistyped_words();
+        if !words.is_empty() {
+            queue!(
+                stdout,
+                Print("MISTYPED WORDS"),
+                MoveToNextLine(1),
+                Print(words.join(" ").red()),
+                MoveToNextLine(2),
+            )?;
+        }
+
+        // source of the phrase
+        if let Some(source) = &result.source {
+            queue!(
+                stdout,
+                Print(format!("— {}", source.clone().italic())),
+                MoveToNextLine(2),
+            )?;
+        }
+
+        // continue message
+        queue!(stdout, Print("Press enter to continue.".italic()))?;
+        stdout.flush()?;
+        pause(None);
+
+        // done
+        Ok(())
+    }
+
+    /// Renders a chart of WPM over each second of the test, with seconds in which errors were made
+    /// marked in red. Tests shorter than two seconds have nothing to chart.
+    fn render_chart(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let samples = self.result.wpm_per_second();
+        if samples.len() < 2 {
+            return Ok(());
+        }
+        let points = samples
+            .iter()
+            .enumerate()
+            .map(|(i, wpm)| (i as f32 + 1., *wpm))
+            .collect::<Vec<(f32, f32)>>();
+        let errors = self
+            .result
+            .timeline
+            .iter()
+            .zip(&points)
+            .filter(|(sample, _)| sample.errors > 0)
+            .map(|(_, point)| *point)
+            .collect::<Vec<(f32, f32)>>();
+        let screen = size()?;
+
+        queue!(stdout, Print("WPM PER SECOND"), MoveToNextLine(1))?;
+        stdout.flush()?;
+        disable_raw_mode()?;
+        Chart::new(
+            (screen.0 as u32 * 2).saturating_sub(10).max(32),
+            (screen.1 as u32 * 2).saturating_sub(48).clamp(12, 60),
+            1.,
+            samples.len() as f32,
+        )
+        .linecolorplot(
+            &Shape::Lines(&points),
+            RGB {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+        )
+        .linecolorplot(&Shape::Points(&errors), RGB { r: 255, g: 0, b: 0 })
+        .display();
+        enable_raw_mode()?;
+        Ok(())
+    }
+
+    /// Renders a comparison of this test against the personal best and rolling average of the
+    /// tests of the same kind taken before it.
+    fn render_comparison(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let result = self.result;
+        let kind = result.kind();
+        let same = self
+            .previous
+            .iter()
+            .filter(|test| test.kind() == kind)
+            .collect::<Vec<&TestResult>>();
+        let Some(pb) = same.iter().map(|test| test.wpm.1).reduce(f32::max) else {
+            queue!(
+                stdout,
+                Print("First test of this kind!".italic()),
+                MoveToNextLine(2)
+            )?;
+            return Ok(());
+        };
+
+        // personal best and rolling average
+        let recent = &same[same.len().saturating_sub(ROLLING_AVERAGE)..];
+        let average = recent.iter().map(|test| test.wpm.1).sum::<f32>() / recent.len() as f32;
+        let delta = |v: f32| {
+            let delta = result.wpm.1 - v;
+            if delta >= 0. {
+                format!("+{delta:.2}").green()
+            } else {
+                format!("{delta:.2}").red()
+            }
+        };
+        queue!(
+            stdout,
+            Print(format!("|{:^16}| {:.2}wpm ({})", "pb", pb, delta(pb))),
+            MoveToNextLine(1),
+            Print(format!(
+                "|{:^16}| {:.2}wpm ({})",
+                format!("average of {}", recent.len()),
+                average,
+                delta(average)
+            )),
+            MoveToNextLine(1),
+        )?;
+        if result.wpm.1 > pb {
+            queue!(
+                stdout,
+                Print(format!("{} {}", "".yellow(), "new pb!".italic())),
+                MoveToNextLine(1),
+            )?;
+        }
+        queue!(stdout, MoveToNextLine(1))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use flate2::bufread::GzDecoder;
+use rand::prelude::IndexedRandom;
+use serde_derive::{Deserialize, Serialize};
+use std::{fmt::Display, io::Read};
+
+/// Line separating snippets within a snippet corpus.
+const SEPARATOR: &str = "%%";
+
+/// Macro to generate `CodeLanguage`, along with a function to get a random code snippet in the
+/// given language.
+macro_rules! snippets {
+    ($($variant:ident ($name:literal) => $content:expr),* $(,)?) => {
+        /// Language of a code snippet.
+        #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+        pub enum CodeLanguage {
+            $($variant),*
+        }
+
+        impl CodeLanguage {
+            /// Returns an iterator over all enum variants
+            pub fn iter() -> impl Iterator<Item = Self> {
+                [$(Self::$variant),*].into_iter()
+            }
+
+            /// Decompresses and splits every snippet in this language.
+            pub fn snippets(&self) -> Vec<String> {
+                let mut decoder = match self {
+                    $(Self::$variant => GzDecoder::new(&$content[..])),*
+                };
+                let mut buf = String::new();
+                decoder
+                    .read_to_string(&mut buf)
+                    .expect("Failed to decompress requested snippets.");
+                split_snippets(&buf)
+            }
+        }
+
+        impl Display for CodeLanguage {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $(Self::$variant => write!(f, $name)),*
+                }
+            }
+        }
+    };
+}
+
+snippets!(
+    C("c") => include_bytes!("../../wordlist/snippets_c.txt.gz"),
+    Cpp("c++") => include_bytes!("../../wordlist/snippets_cpp.txt.gz"),
+    JavaScript("javascript") => include_bytes!("../../wordlist/snippets_javascript.txt.gz"),
+    Rust("rust") => include_bytes!("../../wordlist/snippets_rust.txt.gz"),
+);
+
+impl CodeLanguage {
+    /// Selects a random snippet in this language.
+    pub fn random_snippet(&self) -> String {
+        self.snippets()
+            .choose(&mut rand::rng())
+            .unwrap_or_else(|| panic!("no snippets found for '{self}'"))
+            .clone()
+    }
+}
+
+/// Splits a snippet corpus into its snippets. Trailing whitespace is trimmed from every line, and
+/// tabs are expanded to four spaces.
+fn split_snippets(src: &str) -> Vec<String> {
+    let mut snippets = vec![];
+    let mut lines: Vec<String> = vec![];
+    for line in src.lines().chain([SEPARATOR]) {
+        if line.trim() == SEPARATOR {
+            let snippet = lines.join("\n").trim_matches('\n').to_string();
+            if !snippet.is_empty() {
+                snippets.push(snippet);
+            }
+            lines.clear();
+        } else {
+            lines.push(line.trim_end().replace('\t', "    "));
+        }
+    }
+    snippets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snippets_split() {
+        let snippets = split_snippets("a\n\tb  \n%%\n\nc\n%%\n");
+        assert_eq!(snippets, vec!["a\n    b", "c"]);
+    }
+
+    #[test]
+    fn every_language_has_snippets() {
+        for language in CodeLanguage::iter() {
+            let snippets = language.snippets();
+            assert!(!snippets.is_empty());
+            // like quotes, snippets are kept to characters every keyboard can type
+            assert!(snippets.iter().all(|s| s.is_ascii()));
+        }
+    }
+}
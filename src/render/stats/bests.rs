@@ -0,0 +1,43 @@
+use super::*;
+use crate::profile::personal_bests;
+
+impl StatsRenderer<'_> {
+    /// Renders a table of personal bests on each kind of test taken, fastest first.
+    pub(super) fn render_bests(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let mut pbs = personal_bests(self.history());
+        pbs.sort_by(|a, b| b.wpm.total_cmp(&a.wpm));
+
+        // title and header
+        let width = pbs
+            .iter()
+            .map(|pb| pb.kind.to_string().chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        queue!(
+            stdout,
+            Print("PERSONAL BESTS"),
+            MoveToNextLine(2),
+            Print(format!("|{:^width$}|{:^12}|{:^12}|", "test", "net", "accuracy").bold()),
+            MoveToNextLine(1),
+        )?;
+
+        // rows
+        for pb in &pbs {
+            queue!(
+                stdout,
+                Print(format!(
+                    "|{:^width$}|{:^12}|{:^12}|",
+                    pb.kind.to_string(),
+                    format!("{:.1}wpm", pb.wpm),
+                    format!("{:.1}%", pb.accuracy),
+                )),
+                MoveToNextLine(1)
+            )?;
+        }
+
+        // done
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use super::*;
+use crate::analysis::{daily_activity, streaks, DayStats};
+
+/// Most weeks shown on the calendar.
+const MAX_WEEKS: u64 = 52;
+
+/// Labels of each row of the calendar, starting on monday.
+const WEEKDAYS: [&str; 7] = ["mon", "", "wed", "", "fri", "", ""];
+
+/// Abbreviated month names.
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+/// Number of recent active days listed below the calendar.
+const RECENT_DAYS: usize = 5;
+
+impl StatsRenderer<'_> {
+    /// Renders a calendar of recent weeks, with each day colored by the number of tests taken or
+    /// the best net WPM on that day, along with practice streaks.
+    pub(super) fn render_calendar(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let days = daily_activity(self.history());
+        let today = unix_now() / DAY;
+        let screen = size().unwrap();
+
+        // title
+        let title = match self.calendar_best {
+            false => "TESTS PER DAY",
+            true => "DAILY BEST",
+        };
+        queue!(stdout, Print(title), MoveToNextLine(2))?;
+
+        // find the first monday shown. the epoch was on a thursday
+        let weeks = ((screen.0 as u64).saturating_sub(6) / 2).clamp(1, MAX_WEEKS);
+        let monday = today - (today + 3) % 7;
+        let start = monday.saturating_sub((weeks - 1) * 7);
+        let value = |day: &DayStats| match self.calendar_best {
+            false => day.tests as f32,
+            true => day.best,
+        };
+        let max = days.values().map(value).fold(0., f32::max).max(1.);
+
+        // month labels, above the first week of each month
+        let mut labels = String::from("    ");
+        let mut week = 0;
+        while week < weeks {
+            let (_, month, day) = civil_from_days((start + week * 7) as i64);
+            if day <= 7 && week + 2 <= weeks {
+                labels += &format!("{:<6}", MONTHS[month as usize - 1]);
+                week += 3;
+            } else {
+                labels += "  ";
+                week += 1;
+            }
+        }
+        queue!(stdout, Print(labels.dark_grey()), MoveToNextLine(1))?;
+
+        // one row per weekday, one column per week
+        for (weekday, label) in WEEKDAYS.iter().enumerate() {
+            queue!(stdout, Print(format!("{label:<4}").dark_grey()))?;
+            for week in 0..weeks {
+                let day = start + week * 7 + weekday as u64;
+                if day > today {
+                    break;
+                }
+                match days.get(&day) {
+                    Some(stats) => {
+                        let color = color_lerp((20, 80, 20), (60, 230, 60), value(stats) / max);
+                        queue!(stdout, Print("■ ".with(color)))?;
+                    }
+                    None => queue!(stdout, Print("■ ".dark_grey()))?,
+                }
+            }
+            queue!(stdout, MoveToNextLine(1))?;
+        }
+
+        // streaks
+        let (current, longest) = streaks(&days, today);
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(format!("|{:^20}| {} days", "current streak", current)),
+            MoveToNextLine(1),
+            Print(format!("|{:^20}| {} days", "longest streak", longest)),
+            MoveToNextLine(2),
+        )?;
+
+        // most recent active days
+        for (day, stats) in days.iter().rev().take(RECENT_DAYS) {
+            queue!(
+                stdout,
+                Print(format!(
+                    "|{:^20}| {} tests, best {:.1}wpm",
+                    format_date(day * DAY),
+                    stats.tests,
+                    stats.best
+                )),
+                MoveToNextLine(1)
+            )?;
+        }
+        if days.is_empty() {
+            queue!(
+                stdout,
+                Print("No dated tests match the current filters.".italic()),
+                MoveToNextLine(1)
+            )?;
+        }
+
+        // add message
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(
+                "Press m to switch between tests per day and daily best. Dates are in UTC."
+                    .italic()
+            )
+        )?;
+
+        // done
+        Ok(())
+    }
+}
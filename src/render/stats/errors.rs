@@ -0,0 +1,109 @@
+use super::*;
+use crate::analysis::{confusions, Confusion};
+
+/// Number of expected and typed characters shown along each side of the confusion matrix.
+const MATRIX_SIZE: usize = 10;
+
+/// Number of substitutions listed below the confusion matrix.
+const WORST_CONFUSIONS: usize = 8;
+
+/// Formats a character so that whitespace is visible.
+fn display(c: char) -> String {
+    match c {
+        ' ' => String::from("␣"),
+        '\n' => String::from("↵"),
+        c => c.to_string(),
+    }
+}
+
+impl StatsRenderer<'_> {
+    /// Renders a confusion matrix of expected against typed characters, for the characters that
+    /// are most often substituted, along with the most common substitutions.
+    pub(super) fn render_errors(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let confusions = confusions(self.history());
+
+        // title
+        queue!(
+            stdout,
+            Print("ERRORS (expected ↓, typed →)"),
+            MoveToNextLine(2)
+        )?;
+        if confusions.is_empty() {
+            queue!(
+                stdout,
+                Print("No mistakes have been recorded yet.".italic())
+            )?;
+            return Ok(());
+        }
+
+        // pick the characters involved in the most substitutions for each axis
+        let axis = |key: fn(&Confusion) -> char| {
+            let mut counts: Vec<(char, usize)> = vec![];
+            for confusion in &confusions {
+                match counts.iter_mut().find(|(c, _)| *c == key(confusion)) {
+                    Some((_, count)) => *count += confusion.count,
+                    None => counts.push((key(confusion), confusion.count)),
+                }
+            }
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            counts
+                .into_iter()
+                .take(MATRIX_SIZE)
+                .map(|(c, _)| c)
+                .collect::<Vec<char>>()
+        };
+        let rows = axis(|confusion| confusion.expected);
+        let cols = axis(|confusion| confusion.typed);
+
+        // matrix, with each cell showing how often the column was typed in place of the row
+        let max = confusions.iter().map(|c| c.rate).fold(0., f32::max);
+        queue!(stdout, Print("     "))?;
+        for col in &cols {
+            queue!(stdout, Print(format!("{:^5}", display(*col)).bold()))?;
+        }
+        queue!(stdout, MoveToNextLine(1))?;
+        for row in &rows {
+            queue!(stdout, Print(format!("{:^5}", display(*row)).bold()))?;
+            for col in &cols {
+                let cell = confusions
+                    .iter()
+                    .find(|c| c.expected == *row && c.typed == *col);
+                match cell {
+                    Some(cell) => {
+                        let color = color_lerp((60, 60, 60), (220, 30, 30), cell.rate / max);
+                        queue!(
+                            stdout,
+                            Print(
+                                format!("{:^5}", format!("{:.0}%", cell.rate * 100.))
+                                    .white()
+                                    .on(color)
+                            )
+                        )?;
+                    }
+                    None => queue!(stdout, Print(format!("{:^5}", "·").dark_grey()))?,
+                }
+            }
+            queue!(stdout, MoveToNextLine(1))?;
+        }
+        queue!(stdout, MoveToNextLine(1))?;
+
+        // list the most common substitutions
+        for confusion in confusions.iter().take(WORST_CONFUSIONS) {
+            queue!(
+                stdout,
+                Print(format!(
+                    "you type {} when {} is expected {:.0}% of the time ({}x)",
+                    display(confusion.typed).bold(),
+                    display(confusion.expected).bold(),
+                    confusion.rate * 100.,
+                    confusion.count
+                )),
+                MoveToNextLine(1)
+            )?;
+        }
+
+        // done
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use super::*;
+use crate::render::{test::Modifiers, wordlist::Wordlist};
+use crossterm::cursor::{MoveRight, MoveUp};
+use std::fmt::Display;
+
+/// Numbers of most recent tests the history can be limited to.
+const LAST_OPTIONS: [usize; 4] = [10, 25, 50, 100];
+
+/// Numbers of days the history can be limited to.
+const DAYS_OPTIONS: [u64; 4] = [7, 30, 90, 365];
+
+/// Combinations of modifiers the history can be limited to.
+const MODIFIERS_OPTIONS: [Modifiers; 4] = [
+    Modifiers {
+        punctuation: false,
+        numbers: false,
+    },
+    Modifiers {
+        punctuation: true,
+        numbers: false,
+    },
+    Modifiers {
+        punctuation: false,
+        numbers: true,
+    },
+    Modifiers {
+        punctuation: true,
+        numbers: true,
+    },
+];
+
+/// Labels of each filter, in the order they are listed in the filter editor.
+const FILTERS: [&str; 5] = ["mode", "wordlist", "modifiers", "days", "last"];
+
+/// Filters limiting which tests statistics are computed over.
+#[derive(Default)]
+pub struct Filters {
+    /// Only keep tests taken in this mode.
+    pub mode: Option<TestMode>,
+    /// Only keep tests taken on this wordlist. Tests in modes that don't use a wordlist are
+    /// dropped.
+    pub wordlist: Option<Wordlist>,
+    /// Only keep tests taken with exactly these modifiers. Tests in modes that don't use a
+    /// wordlist are dropped, since modifiers don't apply to them.
+    pub modifiers: Option<Modifiers>,
+    /// Only keep tests taken within this many days. Tests without a timestamp are dropped.
+    pub days: Option<u64>,
+    /// Only keep this many of the most recent tests remaining after the other filters.
+    pub last: Option<usize>,
+}
+
+impl Filters {
+    /// Returns true if no filters are set.
+    pub fn is_empty(&self) -> bool {
+        self.mode.is_none()
+            && self.wordlist.is_none()
+            && self.modifiers.is_none()
+            && self.days.is_none()
+            && self.last.is_none()
+    }
+
+    /// Gets the tests in `history` that pass every filter, in order.
+    pub fn apply<'a>(&self, history: &'a [TestResult]) -> Vec<&'a TestResult> {
+        self.indices(history)
+            .into_iter()
+            .map(|idx| &history[idx])
+            .collect()
+    }
+
+    /// Gets the indices of the tests in `history` that pass every filter, in order.
+    pub fn indices(&self, history: &[TestResult]) -> Vec<usize> {
+        let since = self.days.map(|days| unix_now().saturating_sub(days * DAY));
+        let indices = history
+            .iter()
+            .enumerate()
+            .filter(|(_, test)| self.mode.as_ref().is_none_or(|mode| test.mode == *mode))
+            .filter(|(_, test)| {
+                self.wordlist
+                    .as_ref()
+                    .is_none_or(|wordlist| test.mode.uses_wordlist() && test.wordlist == *wordlist)
+            })
+            .filter(|(_, test)| {
+                self.modifiers.is_none_or(|modifiers| {
+                    test.mode.uses_wordlist() && test.modifiers == modifiers
+                })
+            })
+            .filter(|(_, test)| since.is_none_or(|since| test.timestamp >= Some(since)))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>();
+        match self.last {
+            Some(n) => indices[indices.len().saturating_sub(n)..].to_vec(),
+            None => indices,
+        }
+    }
+}
+
+impl Display for Filters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut filters = vec![];
+        if let Some(mode) = &self.mode {
+            filters.push(mode.to_string());
+        }
+        if let Some(wordlist) = &self.wordlist {
+            filters.push(wordlist.name());
+        }
+        if let Some(modifiers) = self.modifiers {
+            filters.push(modifiers_label(modifiers));
+        }
+        if let Some(days) = self.days {
+            filters.push(format!("last {days} days"));
+        }
+        if let Some(last) = self.last {
+            filters.push(format!("last {last} tests"));
+        }
+        write!(f, "{}", filters.join(", "))
+    }
+}
+
+impl StatsRenderer<'_> {
+    /// Gets the tests statistics are computed over, with the current filters applied.
+    pub(super) fn history(&self) -> Vec<&TestResult> {
+        self.filters.apply(self.profile.get_history())
+    }
+
+    /// Gets the options of the filter at `idx`, as labels, along with the index of the one
+    /// selected. The first option always clears the filter.
+    fn filter_options(&self, idx: usize) -> (Vec<String>, usize) {
+        let history = self.profile.get_history();
+        let mut options = vec![String::from("all")];
+        let selected = match idx {
+            0 => {
+                let modes = distinct(history.iter().map(|test| &test.mode));
+                options.extend(modes.iter().map(|mode| mode.to_string()));
+                self.filters
+                    .mode
+                    .as_ref()
+                    .and_then(|mode| modes.iter().position(|m| *m == mode))
+            }
+            1 => {
+                let wordlists = wordlists(history);
+                options.extend(wordlists.iter().map(|wordlist| wordlist.name()));
+                self.filters
+                    .wordlist
+                    .as_ref()
+                    .and_then(|wordlist| wordlists.iter().position(|w| *w == wordlist))
+            }
+            2 => {
+                options.extend(MODIFIERS_OPTIONS.into_iter().map(modifiers_label));
+                self.filters
+                    .modifiers
+                    .and_then(|modifiers| MODIFIERS_OPTIONS.iter().position(|m| *m == modifiers))
+            }
+            3 => {
+                options.extend(DAYS_OPTIONS.iter().map(|n| format!("{n} days")));
+                self.filters
+                    .days
+                    .and_then(|days| DAYS_OPTIONS.iter().position(|n| *n == days))
+            }
+            _ => {
+                options.extend(LAST_OPTIONS.iter().map(|n| format!("{n} tests")));
+                self.filters
+                    .last
+                    .and_then(|last| LAST_OPTIONS.iter().position(|n| *n == last))
+            }
+        };
+        (options, selected.map_or(0, |i| i + 1))
+    }
+
+    /// Sets the filter at `idx` to the option at `option`, as listed by `filter_options`.
+    fn set_filter(&mut self, idx: usize, option: usize) {
+        let history = self.profile.get_history();
+        let option = option.checked_sub(1);
+        match idx {
+            0 => {
+                let modes = distinct(history.iter().map(|test| &test.mode));
+                self.filters.mode = option.and_then(|i| modes.get(i)).map(|m| (*m).clone());
+            }
+            1 => {
+                let wordlists = wordlists(history);
+                self.filters.wordlist = option.and_then(|i| wordlists.get(i)).map(|w| (*w).clone());
+            }
+            2 => self.filters.modifiers = option.and_then(|i| MODIFIERS_OPTIONS.get(i)).copied(),
+            3 => self.filters.days = option.and_then(|i| DAYS_OPTIONS.get(i)).copied(),
+            _ => self.filters.last = option.and_then(|i| LAST_OPTIONS.get(i)).copied(),
+        }
+    }
+
+    /// Opens the filter editor until the user exits it. Filters are navigated like the menu: j/k
+    /// to move, enter to open a filter or pick an option, and ESC to go back.
+    pub(super) fn edit_filters(&mut self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let mut cursor = vec![0];
+        loop {
+            // list filters, along with their current value
+            clear(&mut stdout);
+            queue!(
+                stdout,
+                MoveTo(0, 0),
+                Print("FILTERS".on_dark_grey().grey()),
+                Print(" j/k: move, enter: select, esc: back".dark_grey().italic()),
+                MoveToNextLine(2),
+            )?;
+            let mut width = 0;
+            for (idx, filter) in FILTERS.iter().enumerate() {
+                let (options, selected) = self.filter_options(idx);
+                let label = format!("{} ({})", filter, options[selected]);
+                width = width.max(label.chars().count());
+                if idx == cursor[0] && cursor.len() == 1 {
+                    queue!(stdout, Print(label.dark_green().on_dark_grey()))?;
+                } else if idx == cursor[0] {
+                    queue!(stdout, Print(label.dark_grey()))?;
+                } else {
+                    queue!(stdout, Print(label))?;
+                }
+                queue!(stdout, MoveToNextLine(1))?;
+            }
+
+            // list the options of the open filter beside it
+            if let Some(option) = cursor.get(1) {
+                let (options, _) = self.filter_options(cursor[0]);
+                queue!(stdout, MoveUp(FILTERS.len() as u16))?;
+                for (idx, label) in options.iter().enumerate() {
+                    queue!(stdout, MoveRight((width + 2) as u16))?;
+                    if idx == *option {
+                        queue!(stdout, Print(label.clone().grey().on_dark_grey()))?;
+                    } else {
+                        queue!(stdout, Print(label))?;
+                    }
+                    queue!(stdout, MoveToNextLine(1))?;
+                }
+            }
+            stdout.flush()?;
+
+            // handle input
+            use KeyCode::*;
+            if let Event::Key(key) = read()? {
+                let len = match cursor.len() {
+                    1 => FILTERS.len(),
+                    _ => self.filter_options(cursor[0]).0.len(),
+                };
+                let last = cursor.last_mut().unwrap(); // safe unwrap, never empty
+                match key.code {
+                    Down | Char('j') => *last = (*last + 1).min(len - 1),
+                    Up | Char('k') => *last = last.saturating_sub(1),
+                    Enter if cursor.len() == 1 => {
+                        let (_, selected) = self.filter_options(cursor[0]);
+                        cursor.push(selected);
+                    }
+                    Enter => {
+                        self.set_filter(cursor[0], cursor[1]);
+                        cursor.pop();
+                    }
+                    Esc if cursor.len() > 1 => {
+                        cursor.pop();
+                    }
+                    Esc => break,
+                    _ => {}
+                }
+            }
+        }
+
+        // done
+        Ok(())
+    }
+}
+
+/// Labels a combination of modifiers, as an option of the modifiers filter.
+fn modifiers_label(modifiers: Modifiers) -> String {
+    if modifiers.is_empty() {
+        String::from("no modifiers")
+    } else {
+        format!("with {modifiers}")
+    }
+}
+
+/// Gets the distinct wordlists of the tests in `history` that were generated from one.
+fn wordlists(history: &[TestResult]) -> Vec<&Wordlist> {
+    distinct(
+        history
+            .iter()
+            .filter(|test| test.mode.uses_wordlist())
+            .map(|test| &test.wordlist),
+    )
+}
+
+/// Gets the distinct values of an iterator, in the order they first appear.
+fn distinct<'a, T: PartialEq>(values: impl Iterator<Item = &'a T>) -> Vec<&'a T> {
+    let mut distinct: Vec<&T> = vec![];
+    for value in values {
+        if !distinct.contains(&value) {
+            distinct.push(value);
+        }
+    }
+    distinct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::quote::QuoteLength;
+    use std::time::Duration;
+
+    /// Creates a test result in the given mode and wordlist.
+    fn test_result(mode: TestMode, wordlist: Wordlist) -> TestResult {
+        TestResult::builder(mode, wordlist)
+            .counts(10, 0)
+            .time(Duration::from_secs(1))
+            .build()
+    }
+
+    #[test]
+    fn filters_apply_in_order() {
+        let history = vec![
+            test_result(TestMode::Words(10), Wordlist::English1k),
+            test_result(TestMode::Words(25), Wordlist::English1k),
+            test_result(TestMode::Words(10), Wordlist::English5k),
+            test_result(TestMode::Words(10), Wordlist::English1k),
+            test_result(TestMode::Quote(QuoteLength::Any), Wordlist::English1k),
+        ];
+        assert_eq!(Filters::default().apply(&history).len(), 5);
+
+        // the last n tests are taken after the other filters
+        let filters = Filters {
+            mode: Some(TestMode::Words(10)),
+            last: Some(2),
+            ..Default::default()
+        };
+        let tests = filters.apply(&history);
+        assert_eq!(tests.len(), 2);
+        assert!(std::ptr::eq(tests[0], &history[2]));
+        assert_eq!(filters.to_string(), "words 10, last 2 tests");
+
+        let filters = Filters {
+            wordlist: Some(Wordlist::English1k),
+            ..filters
+        };
+        assert_eq!(filters.apply(&history).len(), 2);
+
+        // tests that don't use a wordlist never match one, even though they store it
+        let filters = Filters {
+            wordlist: Some(Wordlist::English1k),
+            ..Default::default()
+        };
+        assert_eq!(filters.apply(&history).len(), 3);
+        assert_eq!(wordlists(&history[4..]), Vec::<&Wordlist>::new());
+
+        // modifiers are matched exactly
+        let mut history = history;
+        history[1].modifiers.punctuation = true;
+        let filters = Filters {
+            modifiers: Some(MODIFIERS_OPTIONS[1]),
+            ..Default::default()
+        };
+        let tests = filters.apply(&history);
+        assert_eq!(tests.len(), 1);
+        assert!(std::ptr::eq(tests[0], &history[1]));
+        assert_eq!(filters.to_string(), "with punctuation");
+        let filters = Filters {
+            modifiers: Some(Modifiers::default()),
+            ..Default::default()
+        };
+        assert_eq!(filters.apply(&history).len(), 3);
+
+        // tests without a timestamp are never within a number of days
+        let filters = Filters {
+            days: Some(7),
+            ..Default::default()
+        };
+        assert!(filters.apply(&history).is_empty());
+    }
+}
@@ -0,0 +1,237 @@
+use super::*;
+use crate::render::results::ResultsRenderer;
+use std::cmp::Ordering;
+
+/// Column the history table is sorted by.
+#[derive(Clone, Copy, PartialEq)]
+pub enum HistorySort {
+    /// Order the tests were taken in.
+    Taken,
+    Date,
+    Mode,
+    Wordlist,
+    Gross,
+    Net,
+    Accuracy,
+    Time,
+}
+
+impl HistorySort {
+    /// All columns, in the order they are shown in.
+    const ALL: [HistorySort; 8] = [
+        HistorySort::Taken,
+        HistorySort::Date,
+        HistorySort::Mode,
+        HistorySort::Wordlist,
+        HistorySort::Gross,
+        HistorySort::Net,
+        HistorySort::Accuracy,
+        HistorySort::Time,
+    ];
+
+    /// Switches to the next column.
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL.iter().position(|v| v == self).unwrap();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// Header label of this column.
+    fn label(&self) -> &'static str {
+        match self {
+            HistorySort::Taken => "#",
+            HistorySort::Date => "date",
+            HistorySort::Mode => "mode",
+            HistorySort::Wordlist => "wordlist",
+            HistorySort::Gross => "gross",
+            HistorySort::Net => "net",
+            HistorySort::Accuracy => "accuracy",
+            HistorySort::Time => "time",
+        }
+    }
+
+    /// Width of this column.
+    fn width(&self) -> usize {
+        match self {
+            HistorySort::Taken => 6,
+            HistorySort::Date => 12,
+            HistorySort::Mode => 24,
+            HistorySort::Wordlist => 24,
+            _ => 11,
+        }
+    }
+
+    /// Compares two tests by this column, given their indices in the history.
+    fn compare(&self, a: (usize, &TestResult), b: (usize, &TestResult)) -> Ordering {
+        match self {
+            HistorySort::Taken => a.0.cmp(&b.0),
+            HistorySort::Date => a.1.timestamp.cmp(&b.1.timestamp),
+            HistorySort::Mode => a.1.mode.to_string().cmp(&b.1.mode.to_string()),
+            HistorySort::Wordlist => a.1.wordlist.name().cmp(&b.1.wordlist.name()),
+            HistorySort::Gross => a.1.wpm.0.total_cmp(&b.1.wpm.0),
+            HistorySort::Net => a.1.wpm.1.total_cmp(&b.1.wpm.1),
+            HistorySort::Accuracy => a.1.accuracy.total_cmp(&b.1.accuracy),
+            HistorySort::Time => a.1.time.cmp(&b.1.time),
+        }
+    }
+
+    /// Formats the value of this column for a test, given its index in the history.
+    fn format(&self, idx: usize, test: &TestResult) -> String {
+        match self {
+            HistorySort::Taken => (idx + 1).to_string(),
+            HistorySort::Date => test.timestamp.map_or(String::from("-"), format_date),
+            HistorySort::Mode => test.mode.to_string(),
+            HistorySort::Wordlist if test.mode.uses_wordlist() => test.wordlist.name(),
+            HistorySort::Wordlist => String::from("-"),
+            HistorySort::Gross => format!("{:.1}wpm", test.wpm.0),
+            HistorySort::Net => format!("{:.1}wpm", test.wpm.1),
+            HistorySort::Accuracy => format!("{:.1}%", test.accuracy),
+            HistorySort::Time => format!("{:.1}s", test.time.as_secs_f32()),
+        }
+    }
+}
+
+impl StatsRenderer<'_> {
+    /// Gets the indices of the tests shown in the history table, sorted by the current column.
+    fn history_rows(&self) -> Vec<usize> {
+        let history = self.profile.get_history();
+        let mut rows = self.filters.indices(history);
+        rows.sort_by(|a, b| {
+            let order = self
+                .history_sort
+                .compare((*a, &history[*a]), (*b, &history[*b]));
+            if self.history_descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        rows
+    }
+
+    /// Gets the index of the test under the cursor in the history table, if there is one.
+    pub(super) fn history_selected(&self) -> Option<usize> {
+        self.history_rows().get(self.history_cursor).copied()
+    }
+
+    /// Opens the results screen of the test under the cursor.
+    pub(super) fn open_selected(&self) -> Result<(), std::io::Error> {
+        if let Some(idx) = self.history_selected() {
+            // compare against the tests taken before this one, as when it was first shown
+            let history = self.profile.get_history();
+            ResultsRenderer::new(&history[idx], &history[..idx]).render()?;
+        }
+        Ok(())
+    }
+
+    /// Replays the test under the cursor, or explains why it can't be replayed.
+    pub(super) fn replay_selected(&self) -> Result<(), std::io::Error> {
+        let Some(idx) = self.history_selected() else {
+            return Ok(());
+        };
+        let test = &self.profile.get_history()[idx];
+        if test.is_replayable() {
+            return TestRenderer::from_result(test)
+                .replay(&test.keystrokes, self.profile.get_config());
+        }
+        let mut stdout = stdout();
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(
+                format!(
+                    "Test #{} was recorded without keystrokes, and can't be replayed.",
+                    idx + 1
+                )
+                .black()
+                .on_dark_yellow()
+            )
+        )?;
+        stdout.flush()?;
+        read()?;
+        Ok(())
+    }
+
+    /// Deletes the test under the cursor, after asking the user to confirm.
+    pub(super) fn delete_selected(&mut self) -> Result<(), std::io::Error> {
+        let Some(idx) = self.history_selected() else {
+            return Ok(());
+        };
+        let mut stdout = stdout();
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(
+                format!("Delete test #{}? (y/n)", idx + 1)
+                    .black()
+                    .on_dark_red()
+            )
+        )?;
+        stdout.flush()?;
+        if let Event::Key(key) = read()? {
+            if key.code == KeyCode::Char('y') {
+                self.profile.remove(idx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders a table of every test in the history, with the row under the cursor highlighted.
+    pub(super) fn render_history(&mut self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let history = self.profile.get_history();
+        let rows = self.history_rows();
+        let screen = size().unwrap();
+
+        // header, with the sorted column marked
+        queue!(stdout, Print("HISTORY"), MoveToNextLine(2))?;
+        if rows.is_empty() {
+            queue!(
+                stdout,
+                Print("No tests match the current filters.".italic())
+            )?;
+            return Ok(());
+        }
+        let mut header = String::from("|");
+        for column in HistorySort::ALL {
+            let label = match (column == self.history_sort, self.history_descending) {
+                (true, true) => format!("{} ▼", column.label()),
+                (true, false) => format!("{} ▲", column.label()),
+                (false, _) => column.label().to_string(),
+            };
+            header += &format!("{:^width$}|", label, width = column.width());
+        }
+        queue!(stdout, Print(header.bold()), MoveToNextLine(1))?;
+
+        // rows, scrolled so that the cursor stays on screen
+        self.history_cursor = self.history_cursor.min(rows.len() - 1);
+        let shown = (screen.1 as usize).saturating_sub(10).max(1);
+        let first = self.history_cursor.saturating_sub(shown - 1);
+        for (row, idx) in rows.iter().enumerate().skip(first).take(shown) {
+            let mut line = String::from("|");
+            for column in HistorySort::ALL {
+                let value = column.format(*idx, &history[*idx]);
+                let value = value.chars().take(column.width()).collect::<String>();
+                line += &format!("{:^width$}|", value, width = column.width());
+            }
+            if row == self.history_cursor {
+                queue!(stdout, Print(line.black().on_grey()))?;
+            } else {
+                queue!(stdout, Print(line))?;
+            }
+            queue!(stdout, MoveToNextLine(1))?;
+        }
+
+        // add message
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(
+                "j/k: move, s: sort, o: reverse order, enter: details, r: replay, x: delete"
+                    .italic()
+            )
+        )?;
+
+        // done
+        Ok(())
+    }
+}
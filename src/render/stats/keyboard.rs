@@ -0,0 +1,135 @@
+use super::*;
+use crate::analysis::{key_stats, KeyStats};
+use crossterm::cursor::MoveRight;
+
+/// Rows of a US QWERTY keyboard, along with the offset of each row from the left edge.
+const ROWS: [(&str, u16); 4] = [
+    ("`1234567890-=", 0),
+    ("qwertyuiop[]\\", 2),
+    ("asdfghjkl;'", 3),
+    ("zxcvbnm,./", 5),
+];
+
+/// Width of a single key, including the gap after it.
+const KEY_WIDTH: u16 = 4;
+
+/// Number of keys listed below the keyboard.
+const WORST_KEYS: usize = 5;
+
+/// Metric a key heatmap is colored by.
+#[derive(Clone, Copy, PartialEq)]
+pub enum KeyMetric {
+    /// Fraction of presses that were misses.
+    MissRate,
+    /// Average time taken to press a key.
+    Latency,
+}
+
+impl KeyMetric {
+    /// Switches to the other metric.
+    pub fn toggle(&self) -> Self {
+        match self {
+            KeyMetric::MissRate => KeyMetric::Latency,
+            KeyMetric::Latency => KeyMetric::MissRate,
+        }
+    }
+
+    /// Gets this metric from the statistics of a key.
+    fn of(&self, key: &KeyStats) -> f32 {
+        match self {
+            KeyMetric::MissRate => key.miss_rate(),
+            KeyMetric::Latency => key.average_latency().as_secs_f32(),
+        }
+    }
+
+    /// Formats a value of this metric.
+    fn format(&self, v: f32) -> String {
+        match self {
+            KeyMetric::MissRate => format!("{:.1}% missed", v * 100.),
+            KeyMetric::Latency => format!("{:.0}ms", v * 1000.),
+        }
+    }
+}
+
+impl StatsRenderer<'_> {
+    /// Renders a keyboard, with each key colored by the current metric over the profile history.
+    pub(super) fn render_keyboard(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let keys = key_stats(self.history());
+        let metric = self.key_metric;
+        let max = keys
+            .values()
+            .map(|key| metric.of(key))
+            .fold(0., f32::max)
+            .max(f32::EPSILON);
+
+        // title
+        let title = match metric {
+            KeyMetric::MissRate => "MISS RATE",
+            KeyMetric::Latency => "AVERAGE TIME TO PRESS",
+        };
+        queue!(stdout, Print(title), MoveToNextLine(2))?;
+
+        // keyboard, with keys colored from green to red as they get worse. keys without any
+        // recorded presses are left grey
+        for (row, offset) in ROWS {
+            queue!(stdout, MoveRight(offset * 2))?;
+            for c in row.chars() {
+                let label = format!(" {c} ");
+                match keys.get(&c).filter(|key| key.presses > 0) {
+                    Some(key) => {
+                        let color = color_lerp((30, 200, 30), (220, 30, 30), metric.of(key) / max);
+                        queue!(stdout, Print(label.black().on(color)))?;
+                    }
+                    None => queue!(stdout, Print(label.grey().on_dark_grey()))?,
+                }
+                queue!(stdout, MoveRight(KEY_WIDTH - 3))?;
+            }
+            queue!(stdout, MoveToNextLine(1))?;
+        }
+        let space = format!("{:^24}", "space");
+        queue!(stdout, MoveRight(8 * 2))?;
+        match keys.get(&' ').filter(|key| key.presses > 0) {
+            Some(key) => {
+                let color = color_lerp((30, 200, 30), (220, 30, 30), metric.of(key) / max);
+                queue!(stdout, Print(space.black().on(color)))?;
+            }
+            None => queue!(stdout, Print(space.grey().on_dark_grey()))?,
+        }
+        queue!(stdout, MoveToNextLine(2))?;
+
+        // list the worst keys
+        let mut worst = keys
+            .iter()
+            .filter(|(_, key)| key.presses > 0)
+            .collect::<Vec<(&char, &KeyStats)>>();
+        worst.sort_by(|a, b| metric.of(b.1).total_cmp(&metric.of(a.1)));
+        for (c, key) in worst.into_iter().take(WORST_KEYS) {
+            let name = if *c == ' ' {
+                String::from("space")
+            } else {
+                c.to_string()
+            };
+            queue!(
+                stdout,
+                Print(format!(
+                    "|{:^7}| {} ({} presses)",
+                    name,
+                    metric.format(metric.of(key)),
+                    key.presses
+                )),
+                MoveToNextLine(1)
+            )?;
+        }
+
+        // add message
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print("Press m to switch between miss rate and time to press.".italic())
+        )?;
+
+        // done
+        Ok(())
+    }
+}
@@ -1,136 +1,246 @@
+mod bests;
+mod calendar;
+mod errors;
+mod filter;
+mod history;
+mod keyboard;
+mod ngrams;
+mod overview;
+
 use std::io::{stdout, Write};
 
-use super::util::*;
-use crate::profile::Profile;
+use super::{test::TestRenderer, util::*};
+use crate::{
+    profile::Profile,
+    render::test::{TestMode, TestResult},
+};
 use crossterm::{
     cursor::{MoveTo, MoveToNextLine},
+    event::{read, Event, KeyCode},
     queue,
     style::{Print, Stylize},
-    terminal::{disable_raw_mode, enable_raw_mode, size},
+    terminal::size,
 };
-use rgb::RGB;
-use textplots::*;
+use filter::Filters;
+use history::HistorySort;
+use keyboard::KeyMetric;
+use ngrams::NgramSort;
+
+/// Views available within the statistics renderer.
+#[derive(Clone, Copy, PartialEq)]
+enum StatsView {
+    /// WPM chart and general statistics.
+    Overview,
+    /// Table of personal bests.
+    Bests,
+    /// Table of every test taken.
+    History,
+    /// Calendar of daily activity.
+    Calendar,
+    /// Per-key heatmap.
+    Keyboard,
+    /// Table of the slowest n-grams.
+    Ngrams,
+    /// Confusion matrix of expected against typed characters.
+    Errors,
+}
+
+impl StatsView {
+    /// All views, in the order they are shown in.
+    const ALL: [StatsView; 7] = [
+        StatsView::Overview,
+        StatsView::Bests,
+        StatsView::History,
+        StatsView::Calendar,
+        StatsView::Keyboard,
+        StatsView::Ngrams,
+        StatsView::Errors,
+    ];
+
+    /// Tab label of this view.
+    fn label(&self) -> &'static str {
+        match self {
+            StatsView::Overview => "overview",
+            StatsView::Bests => "bests",
+            StatsView::History => "history",
+            StatsView::Calendar => "calendar",
+            StatsView::Keyboard => "keyboard",
+            StatsView::Ngrams => "ngrams",
+            StatsView::Errors => "errors",
+        }
+    }
+
+    /// Gets the view `offset` tabs away from this one, wrapping around.
+    fn offset(&self, offset: isize) -> Self {
+        let idx = Self::ALL.iter().position(|v| v == self).unwrap() as isize;
+        Self::ALL[(idx + offset).rem_euclid(Self::ALL.len() as isize) as usize]
+    }
+}
 
 /// Renders profile statistics.
 pub struct StatsRenderer<'a> {
     /// Profile to view.
-    profile: &'a Profile,
+    profile: &'a mut Profile,
+    /// View currently shown.
+    view: StatsView,
+    /// Metric shown on the keyboard heatmap.
+    key_metric: KeyMetric,
+    /// Length of the n-grams listed in the n-gram table.
+    ngram_size: usize,
+    /// Column the n-gram table is sorted by.
+    ngram_sort: NgramSort,
+    /// Selected row of the n-gram table.
+    ngram_cursor: usize,
+    /// Filters limiting which tests are shown.
+    filters: Filters,
+    /// Column the history table is sorted by.
+    history_sort: HistorySort,
+    /// True when the history table is sorted in descending order.
+    history_descending: bool,
+    /// Selected row of the history table.
+    history_cursor: usize,
+    /// True when the calendar is colored by daily best rather than tests per day.
+    calendar_best: bool,
 }
 
 impl<'a> StatsRenderer<'a> {
-    pub fn new(profile: &'a Profile) -> Self {
-        Self { profile }
+    pub fn new(profile: &'a mut Profile) -> Self {
+        Self {
+            profile,
+            view: StatsView::Overview,
+            key_metric: KeyMetric::MissRate,
+            ngram_size: 2,
+            ngram_sort: NgramSort::Time,
+            ngram_cursor: 0,
+            filters: Filters::default(),
+            history_sort: HistorySort::Taken,
+            history_descending: true,
+            history_cursor: 0,
+            calendar_best: false,
+        }
     }
 
-    /// Renders profile statistics.
-    pub fn render(&mut self) -> Result<(), std::io::Error> {
-        // graph wpm over time TODO this is jus a demo
-        let mut stdout = stdout();
-        let profile = self.profile;
-        let history = profile.get_history();
-        let screen = size().unwrap();
-        clear(&mut stdout);
-
+    /// Renders profile statistics until the user exits. Views are switched between with tab.
+    /// Returns the drill test the user chose to take, if any.
+    pub fn render(&mut self) -> Result<Option<TestMode>, std::io::Error> {
         // first, make sure history isn't too short
-        if history.is_empty() {
+        if self.profile.get_history().is_empty() {
             return Err(std::io::Error::other("No history to display."));
         }
 
-        // gross wpm chart
-        queue!(
-            stdout,
-            MoveTo(0, 0),
-            Print("AVERAGE WPM"),
-            MoveToNextLine(1)
-        )?;
-        disable_raw_mode()?;
-        Chart::new(
-            (screen.0 as u32 * 2) - 10,
-            (screen.1 as u32 * 2) - 10,
-            0.,
-            history.len() as f32,
-        )
-        .linecolorplot(
-            &Shape::Continuous(Box::new(|x| {
-                // plot the average wpm with a exponential smoothing function
-                if x > 1. {
-                    let delta: f32 = (x % 1.).powf(2_f32);
-                    let last_step = history.get(x as usize - 1).unwrap().wpm.1;
-                    let this_step = history.get(x as usize).unwrap().wpm.1;
-                    last_step * (1.0 - delta) + this_step * delta
-                } else {
-                    history.first().unwrap().wpm.1
-                }
-            })),
-            RGB {
-                r: 255,
-                g: 255,
-                b: 255,
-            },
-        )
-        .linecolorplot(
-            &Shape::Continuous(Box::new(|x| {
-                // plot the average of five with a exponential smoothing function
-                if x > 1. {
-                    let delta: f32 = (x % 1.).powf(2_f32);
-                    let last_step = self.avg_of_five(x as usize - 1);
-                    let this_step = self.avg_of_five(x as usize);
-                    last_step * (1.0 - delta) + this_step * delta
-                } else {
-                    history.first().unwrap().wpm.1
+        let mut stdout = stdout();
+        loop {
+            // render tabs and the current view
+            clear(&mut stdout);
+            self.render_tabs()?;
+            match self.view {
+                StatsView::Overview => self.render_overview()?,
+                StatsView::Bests => self.render_bests()?,
+                StatsView::History => self.render_history()?,
+                StatsView::Calendar => self.render_calendar()?,
+                StatsView::Keyboard => self.render_keyboard()?,
+                StatsView::Ngrams => self.render_ngrams()?,
+                StatsView::Errors => self.render_errors()?,
+            }
+            stdout.flush()?;
+
+            // wait for user input
+            use KeyCode::*;
+            if let Event::Key(key) = read()? {
+                match key.code {
+                    Enter if self.view == StatsView::History => self.open_selected()?,
+                    Esc | Enter => break,
+                    Tab => self.view = self.view.offset(1),
+                    BackTab => self.view = self.view.offset(-1),
+                    Char('f') => {
+                        self.edit_filters()?;
+                        self.ngram_cursor = 0;
+                        self.history_cursor = 0;
+                    }
+                    Char('r') if self.view == StatsView::Overview => {
+                        if let Some(test) = self.last_replayable() {
+                            TestRenderer::from_result(test)
+                                .replay(&test.keystrokes, self.profile.get_config())?;
+                        }
+                    }
+                    Char('m') if self.view == StatsView::Keyboard => {
+                        self.key_metric = self.key_metric.toggle();
+                    }
+                    Down | Char('j') if self.view == StatsView::Ngrams => {
+                        self.ngram_cursor += 1;
+                    }
+                    Up | Char('k') if self.view == StatsView::Ngrams => {
+                        self.ngram_cursor = self.ngram_cursor.saturating_sub(1);
+                    }
+                    Char('s') if self.view == StatsView::Ngrams => {
+                        self.ngram_sort = self.ngram_sort.next();
+                        self.ngram_cursor = 0;
+                    }
+                    Char('n') if self.view == StatsView::Ngrams => {
+                        self.ngram_size = if self.ngram_size == 2 { 3 } else { 2 };
+                        self.ngram_cursor = 0;
+                    }
+                    Down | Char('j') if self.view == StatsView::History => {
+                        self.history_cursor += 1;
+                    }
+                    Up | Char('k') if self.view == StatsView::History => {
+                        self.history_cursor = self.history_cursor.saturating_sub(1);
+                    }
+                    Char('s') if self.view == StatsView::History => {
+                        self.history_sort = self.history_sort.next();
+                        self.history_cursor = 0;
+                    }
+                    Char('o') if self.view == StatsView::History => {
+                        self.history_descending = !self.history_descending;
+                        self.history_cursor = 0;
+                    }
+                    Char('r') if self.view == StatsView::History => self.replay_selected()?,
+                    Char('x') if self.view == StatsView::History => self.delete_selected()?,
+                    Char('m') if self.view == StatsView::Calendar => {
+                        self.calendar_best = !self.calendar_best;
+                    }
+                    Char('d') if self.view == StatsView::Ngrams => {
+                        if let Some(drill) = self.ngram_drill() {
+                            return Ok(Some(drill));
+                        }
+                    }
+                    _ => {}
                 }
-            })),
-            RGB {
-                r: 145,
-                g: 145,
-                b: 145,
-            },
-        )
-        .display();
-        enable_raw_mode()?;
+            }
+        }
 
-        // render some simple profile stats
-        let stats = profile.get_stats();
+        // done
+        Ok(None)
+    }
+
+    /// Renders the tab bar, and moves to the line below it.
+    fn render_tabs(&self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        queue!(stdout, MoveTo(0, 0))?;
+        for view in StatsView::ALL {
+            let label = format!(" {} ", view.label());
+            if view == self.view {
+                queue!(stdout, Print(label.black().on_grey()))?;
+            } else {
+                queue!(stdout, Print(label.grey().on_dark_grey()))?;
+            }
+        }
         queue!(
             stdout,
-            MoveToNextLine(1),
-            Print(format!(
-                "|{:^32}| {}",
-                "total tests taken", stats.total_tests
-            )),
-            MoveToNextLine(1),
-            Print(format!(
-                "|{:^32}| {:.1}wpm",
-                "average gross", stats.average_gross_wpm
-            )),
-            MoveToNextLine(1),
-            Print(format!(
-                "|{:^32}| {:.1}wpm",
-                "average net", stats.average_net_wpm
-            )),
-            MoveToNextLine(1),
-            Print(format!("|{:^32}| {:.1}wpm", "personal best", stats.pb)),
-            MoveToNextLine(3),
+            Print(
+                " tab: switch view, f: filter, enter: exit"
+                    .dark_grey()
+                    .italic()
+            ),
         )?;
-
-        // add message and flush
-        queue!(stdout, Print("Press enter to exit.".italic()))?;
-        stdout.flush()?;
-
-        // wait for user input
-        pause(None);
-
-        // done
+        if !self.filters.is_empty() {
+            queue!(
+                stdout,
+                MoveToNextLine(1),
+                Print(format!("filtered to {}", self.filters).dark_yellow())
+            )?;
+        }
+        queue!(stdout, MoveToNextLine(2))?;
         Ok(())
     }
-
-    /// Gets the average net WPM of the test results from `x-5` to `x`.
-    fn avg_of_five(&self, x: usize) -> f32 {
-        let range = x.saturating_sub(4)..=x;
-        let size = range.size_hint();
-        range
-            .map(|i| self.profile.get_history().get(i).unwrap().wpm.1)
-            .sum::<f32>()
-            / size.0 as f32
-    }
 }
@@ -0,0 +1,137 @@
+use super::*;
+use crate::{
+    analysis::{ngram_stats, KeyStats},
+    render::test::TestMode,
+};
+
+/// Minimum number of times an n-gram must have been typed to be listed.
+const MIN_OCCURRENCES: usize = 3;
+
+/// Number of words in a drill launched from the n-gram table.
+const DRILL_WORDS: usize = 25;
+
+/// Column the n-gram table is sorted by. Rows are always sorted worst first.
+#[derive(Clone, Copy, PartialEq)]
+pub enum NgramSort {
+    /// Average transition time into the last character.
+    Time,
+    /// Fraction of transitions that were misses.
+    MissRate,
+    /// Number of times the n-gram was typed.
+    Count,
+}
+
+impl NgramSort {
+    /// Switches to the next column.
+    pub fn next(&self) -> Self {
+        match self {
+            NgramSort::Time => NgramSort::MissRate,
+            NgramSort::MissRate => NgramSort::Count,
+            NgramSort::Count => NgramSort::Time,
+        }
+    }
+}
+
+impl StatsRenderer<'_> {
+    /// Gets every n-gram of the current size that has been typed often enough, sorted by the
+    /// current column.
+    pub(super) fn ngrams(&self) -> Vec<(String, KeyStats)> {
+        let mut ngrams = ngram_stats(self.history(), self.ngram_size)
+            .into_iter()
+            .filter(|(_, stats)| stats.presses >= MIN_OCCURRENCES)
+            .collect::<Vec<(String, KeyStats)>>();
+        ngrams.sort_by(|a, b| match self.ngram_sort {
+            NgramSort::Time => b.1.average_latency().cmp(&a.1.average_latency()),
+            NgramSort::MissRate => b.1.miss_rate().total_cmp(&a.1.miss_rate()),
+            NgramSort::Count => b.1.presses.cmp(&a.1.presses),
+        });
+        ngrams
+    }
+
+    /// Gets a drill test over the n-gram under the cursor, if there is one.
+    pub(super) fn ngram_drill(&self) -> Option<TestMode> {
+        self.ngrams()
+            .into_iter()
+            .nth(self.ngram_cursor)
+            .map(|(ngram, _)| TestMode::Drill {
+                ngram,
+                words: DRILL_WORDS,
+            })
+    }
+
+    /// Renders a table of n-grams, with the row under the cursor highlighted.
+    pub(super) fn render_ngrams(&mut self) -> Result<(), std::io::Error> {
+        let mut stdout = stdout();
+        let ngrams = self.ngrams();
+        let screen = size().unwrap();
+
+        // title
+        let title = match self.ngram_size {
+            2 => "BIGRAMS",
+            _ => "TRIGRAMS",
+        };
+        queue!(stdout, Print(title), MoveToNextLine(2))?;
+        if ngrams.is_empty() {
+            queue!(
+                stdout,
+                Print("Not enough tests have been taken yet.".italic()),
+                MoveToNextLine(2)
+            )?;
+        }
+
+        // header, with the sorted column marked
+        let header = |label: &str, sort: NgramSort| {
+            if sort == self.ngram_sort {
+                format!("{label} ▼")
+            } else {
+                label.to_string()
+            }
+        };
+        if !ngrams.is_empty() {
+            queue!(
+                stdout,
+                Print(
+                    format!(
+                        "|{:^7}|{:^12}|{:^12}|{:^12}|",
+                        "ngram",
+                        header("time", NgramSort::Time),
+                        header("missed", NgramSort::MissRate),
+                        header("count", NgramSort::Count),
+                    )
+                    .bold()
+                ),
+                MoveToNextLine(1)
+            )?;
+        }
+
+        // rows, scrolled so that the cursor stays on screen
+        self.ngram_cursor = self.ngram_cursor.min(ngrams.len().saturating_sub(1));
+        let rows = (screen.1 as usize).saturating_sub(10).max(1);
+        let first = self.ngram_cursor.saturating_sub(rows - 1);
+        for (idx, (ngram, stats)) in ngrams.iter().enumerate().skip(first).take(rows) {
+            let row = format!(
+                "|{:^7}|{:^12}|{:^12}|{:^12}|",
+                ngram,
+                format!("{}ms", stats.average_latency().as_millis()),
+                format!("{:.1}%", stats.miss_rate() * 100.),
+                stats.presses,
+            );
+            if idx == self.ngram_cursor {
+                queue!(stdout, Print(row.black().on_grey()))?;
+            } else {
+                queue!(stdout, Print(row))?;
+            }
+            queue!(stdout, MoveToNextLine(1))?;
+        }
+
+        // add message
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print("j/k: move, s: sort, n: bigrams/trigrams, d: drill the selected ngram".italic())
+        )?;
+
+        // done
+        Ok(())
+    }
+}
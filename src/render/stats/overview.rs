@@ -0,0 +1,135 @@
+use super::*;
+use crate::profile::ProfileStatistics;
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use rgb::RGB;
+use textplots::*;
+
+impl StatsRenderer<'_> {
+    /// Renders the profile overview, with a chart of WPM over time and general statistics.
+    pub(super) fn render_overview(&self) -> Result<(), std::io::Error> {
+        // graph wpm over time
+        let mut stdout = stdout();
+        let history = self.history();
+        let screen = size().unwrap();
+        if history.is_empty() {
+            queue!(
+                stdout,
+                Print("No tests match the current filters.".italic())
+            )?;
+            return Ok(());
+        }
+
+        // gross wpm chart
+        queue!(stdout, Print("AVERAGE WPM"), MoveToNextLine(1))?;
+        stdout.flush()?;
+        disable_raw_mode()?;
+        Chart::new(
+            (screen.0 as u32 * 2) - 10,
+            (screen.1 as u32 * 2).saturating_sub(16),
+            0.,
+            history.len() as f32,
+        )
+        .linecolorplot(
+            &Shape::Continuous(Box::new(|x| {
+                // plot the average wpm with a exponential smoothing function
+                if x > 1. {
+                    let delta: f32 = (x % 1.).powf(2_f32);
+                    let last_step = history.get(x as usize - 1).unwrap().wpm.1;
+                    let this_step = history.get(x as usize).unwrap().wpm.1;
+                    last_step * (1.0 - delta) + this_step * delta
+                } else {
+                    history.first().unwrap().wpm.1
+                }
+            })),
+            RGB {
+                r: 255,
+                g: 255,
+                b: 255,
+            },
+        )
+        .linecolorplot(
+            &Shape::Continuous(Box::new(|x| {
+                // plot the average of five with a exponential smoothing function
+                if x > 1. {
+                    let delta: f32 = (x % 1.).powf(2_f32);
+                    let last_step = avg_of_five(&history, x as usize - 1);
+                    let this_step = avg_of_five(&history, x as usize);
+                    last_step * (1.0 - delta) + this_step * delta
+                } else {
+                    history.first().unwrap().wpm.1
+                }
+            })),
+            RGB {
+                r: 145,
+                g: 145,
+                b: 145,
+            },
+        )
+        .display();
+        enable_raw_mode()?;
+
+        // render some simple stats over the shown tests. unfiltered stats are kept up to date on
+        // the profile already
+        let stats = if self.filters.is_empty() {
+            self.profile.get_stats()
+        } else {
+            &ProfileStatistics::from_tests(history.iter().copied())
+        };
+        queue!(
+            stdout,
+            MoveToNextLine(1),
+            Print(format!(
+                "|{:^32}| {}",
+                "total tests taken", stats.total_tests
+            )),
+            MoveToNextLine(1),
+            Print(format!(
+                "|{:^32}| {:.1}wpm",
+                "average gross", stats.average_gross_wpm
+            )),
+            MoveToNextLine(1),
+            Print(format!(
+                "|{:^32}| {:.1}wpm",
+                "average net", stats.average_net_wpm
+            )),
+            MoveToNextLine(1),
+            Print(format!(
+                "|{:^32}| {:.1}%",
+                "average accuracy", stats.average_accuracy
+            )),
+            MoveToNextLine(1),
+            Print(format!("|{:^32}| {:.1}wpm", "personal best", stats.pb)),
+            MoveToNextLine(2),
+        )?;
+
+        // add message
+        if self.last_replayable().is_some() {
+            queue!(
+                stdout,
+                Print(
+                    "Press r to replay your last test, or pick any test to replay from history."
+                        .italic()
+                )
+            )?;
+        }
+
+        // done
+        Ok(())
+    }
+
+    /// Gets the most recent test that can be replayed.
+    pub(super) fn last_replayable(&self) -> Option<&TestResult> {
+        self.profile
+            .get_history()
+            .iter()
+            .rev()
+            .find(|test| test.is_replayable())
+    }
+}
+
+/// Gets the average net WPM of the test results from `x-5` to `x`.
+fn avg_of_five(history: &[&TestResult], x: usize) -> f32 {
+    let range = x.saturating_sub(4)..=x;
+    let size = range.size_hint();
+    range.map(|i| history.get(i).unwrap().wpm.1).sum::<f32>() / size.0 as f32
+}
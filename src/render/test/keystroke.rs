@@ -0,0 +1,27 @@
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A single keypress made during a test. Every keystroke the test reacts to is recorded, in
+/// order, so that the raw event stream can be analyzed after the fact.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Keystroke {
+    /// Time since the start of the test.
+    pub time: Duration,
+    /// Position of the letter this keystroke applied to.
+    pub position: usize,
+    /// Character expected at the cursor. For backspaces, this is the character being erased.
+    pub expected: char,
+    /// Character typed. `None` for backspaces.
+    pub typed: Option<char>,
+    /// True if this keystroke was a backspace.
+    pub backspace: bool,
+    /// True if this keystroke was a backspace that erased a miss.
+    pub correction: bool,
+}
+
+impl Keystroke {
+    /// Returns true if a character was typed, and it matched the expected character.
+    pub fn is_hit(&self) -> bool {
+        self.typed == Some(self.expected)
+    }
+}
@@ -1,8 +1,19 @@
 /// Represents a single letter within the phrase. Each letter is either a `Char`, which is an
 /// untyped character, a `Hit`, which is a correct character, and a `Miss`, which is an incorrect
-/// character.
+/// character. Misses also store the character that was typed instead, or `None` if the letter was
+/// skipped over. Indentation passed over automatically is `Skipped`, and counts as neither.
 pub enum Letter {
     Char(char),
     Hit(char),
-    Miss(char),
+    Miss(char, Option<char>),
+    Skipped(char),
+}
+
+impl Letter {
+    /// Gets the character of this letter, regardless of its state.
+    pub fn char(&self) -> char {
+        match *self {
+            Letter::Char(c) | Letter::Hit(c) | Letter::Miss(c, _) | Letter::Skipped(c) => c,
+        }
+    }
 }
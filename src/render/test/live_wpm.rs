@@ -44,6 +44,14 @@ impl LiveWPM {
             self.last_instant = Instant::now();
         }
 
+        // return calculated WPM
+        self.last_wpm = self.sample();
+        self.last_wpm
+    }
+
+    /// Gives the WPM achieved over the set time window right now, regardless of when it was last
+    /// queried, and trims keypress entries that exceed it.
+    pub fn sample(&mut self) -> f32 {
         // remove entries that exceed the time window
         while let Some(instant) = self.keypresses.front() {
             if instant.elapsed() > WINDOW {
@@ -54,8 +62,7 @@ impl LiveWPM {
         }
 
         // return calculated WPM, ignoring errors
-        self.last_wpm = wpm_gross(self.keypresses.len(), WINDOW);
-        self.last_wpm
+        wpm_gross(self.keypresses.len(), WINDOW)
     }
 }
 
@@ -71,7 +78,7 @@ mod tests {
         tracker.press();
 
         // assert that the press was added to internal `keypresses` buffer
-        assert!(tracker.keypresses.len() > 0);
+        assert!(!tracker.keypresses.is_empty());
     }
 
     #[test]
@@ -1,5 +1,8 @@
+mod keystroke;
 mod letter;
 mod live_wpm;
+mod modifiers;
+mod replay;
 mod test_mode;
 mod test_result;
 
@@ -11,14 +14,16 @@ use std::{
 use super::{util::*, wordlist::Wordlist};
 use crate::config::Config;
 use crossterm::{
-    cursor::{Hide, MoveDown, MoveRight, MoveTo, Show},
+    cursor::{Hide, MoveRight, MoveTo, Show},
     event::{poll, read, Event, KeyCode, KeyEvent},
     execute, queue,
     style::{Print, Stylize},
-    terminal::size,
+    terminal::{size, Clear, ClearType},
 };
+pub use keystroke::*;
 pub use letter::*;
 use live_wpm::*;
+pub use modifiers::*;
 pub use test_mode::*;
 pub use test_result::*;
 
@@ -28,6 +33,9 @@ const PAD_X: u16 = 4;
 /// Base Y padding for the UI.
 const PAD_Y: u16 = 1;
 
+/// Minimum line limit for code tests, which span several lines.
+const CODE_LINE_LIMIT: u16 = 12;
+
 /// Renders a typing test with the given phrase.
 pub struct TestRenderer {
     /// Tracks the live WPM.
@@ -40,6 +48,8 @@ pub struct TestRenderer {
     phrase: String,
     /// Letters of the selected phrase.
     letters: Vec<Letter>,
+    /// Every keystroke made during the test, in order.
+    keystrokes: Vec<Keystroke>,
     /// Test timer.
     timer: Option<Instant>,
     /// Cursor position.
@@ -50,6 +60,10 @@ pub struct TestRenderer {
     text_limit: ((u16, u16), (u16, u16)),
     /// Line limit.
     line_limit: u16,
+    /// True when indentation following a newline is skipped automatically.
+    skip_indentation: bool,
+    /// Live WPM and errors, sampled once per second.
+    timeline: Vec<Sample>,
 }
 
 impl TestRenderer {
@@ -59,22 +73,30 @@ impl TestRenderer {
             wordlist,
             mode,
             phrase: phrase.clone(),
-            letters: phrase
-                .as_bytes()
-                .iter()
-                .map(|c| Letter::Char(*c as char))
-                .collect::<Vec<Letter>>(),
+            letters: phrase.chars().map(Letter::Char).collect::<Vec<Letter>>(),
+            keystrokes: vec![],
             timer: None,
             cursor: 0,
             screen_size: (0, 0),
             text_limit: ((0, 0), (0, 0)),
             line_limit: 0,
+            skip_indentation: false,
+            timeline: vec![],
+        }
+    }
+
+    /// Applies the renderer-specific parts of the given configuration.
+    fn apply_config(&mut self, config: &Config) {
+        self.line_limit = config.get_int("test line limit") as u16;
+        if let TestMode::Code(_) = self.mode {
+            self.line_limit = self.line_limit.max(CODE_LINE_LIMIT);
         }
+        self.skip_indentation = !config.get_bool("require indentation");
     }
 
     /// Returns true if the cursor is in bounds of the phrase.
     fn cursor_in_bounds(&self) -> bool {
-        self.cursor < self.phrase.len()
+        self.cursor < self.letters.len()
     }
 
     /// Updates screen size, and resizes text to fit.
@@ -83,7 +105,7 @@ impl TestRenderer {
         self.text_limit = (
             (
                 (self.screen_size.0 / 2)
-                    .saturating_sub(self.phrase.len() as u16 / 2)
+                    .saturating_sub(self.text_width() as u16 / 2)
                     .saturating_sub(PAD_X)
                     .max(PAD_X),
                 PAD_Y + 2,
@@ -93,11 +115,20 @@ impl TestRenderer {
         Ok(())
     }
 
+    /// Width of the phrase in characters, measured by its longest line.
+    fn text_width(&self) -> usize {
+        self.phrase
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
     /// Renders a test until it is completed, or cancelled by the user. Returns a test result when
     /// applicable, containing information about performance.
     pub fn render(&mut self, config: &Config) -> Result<Option<TestResult>, std::io::Error> {
         // set up variables for the renderer
-        self.line_limit = config.get_int("test line limit") as u16;
+        self.apply_config(config);
         self.apply_screen_limits()?;
         let mut frame_time = Duration::default();
         let mut stdout = stdout(); // stdout handle
@@ -108,62 +139,15 @@ impl TestRenderer {
             // start frametime timer
             let dt = Instant::now();
 
-            // render mode info
-            self.render_mode(&mut stdout)?;
-
-            // render performance indicator
-            if config.get_bool("show performance indicator") {
-                let perf_factor = frame_time.as_secs_f32() / 0.1;
-                queue!(
-                    stdout,
-                    MoveRight(1),
-                    Print("".with(color_lerp((0, 255, 0), (255, 0, 0), perf_factor)))
-                )?;
-            }
-
-            // render live wpm
-            if config.get_bool("show live words per minute") {
-                queue!(
-                    stdout,
-                    MoveRight(1),
-                    Print(format!("WPM: {:>3.1}", self.live_wpm.wpm() as usize).on_dark_grey())
-                )?;
-            }
-
-            // move to the top corner of the draw area and hide
-            queue!(
-                stdout,
-                MoveTo(self.text_limit.0 .0, self.text_limit.0 .1),
-                Hide
-            )?;
-
-            // render textbox
-            self.render_textbox(&mut stdout)?;
-
-            // wrap content in respect to screen limits
-            queue!(stdout, move_to_wrap(self.cursor, self.text_limit.1), Show)?;
-            if self.text_limit.0 .0 > 0 {
-                queue!(stdout, MoveRight(self.text_limit.0 .0))?;
-            }
-            if self.text_limit.0 .1 > 0 {
-                queue!(stdout, MoveDown(self.text_limit.0 .1))?;
-            }
+            // render frame
+            self.render_frame(&mut stdout, config, frame_time)?;
 
             // finished rendering, so flush to terminal
             frame_time = dt.elapsed();
             stdout.flush()?;
 
-            // end condition
-            if match self.mode {
-                TestMode::Words(_) => !self.cursor_in_bounds(),
-                TestMode::Time(duration) => {
-                    if let Some(timer) = self.timer {
-                        timer.elapsed() >= duration
-                    } else {
-                        false
-                    }
-                }
-            } {
+            // sample live wpm every second, then check the end condition
+            if self.tick() {
                 break;
             }
 
@@ -196,98 +180,212 @@ impl TestRenderer {
         clear(&mut stdout);
 
         // if the test was ended early, don't give a score
+        if !self.is_finished() {
+            return Ok(None);
+        }
+
+        // get timer for score report, since the test was not terminated prematurely
+        let timer = self.timer.expect("Timer unexpectedly uninitialized.");
+
+        // create test result
+        let result = TestResult::builder(self.mode.clone(), self.wordlist.clone())
+            .length(self.phrase.split_whitespace().count())
+            .phrase(self.phrase.clone())
+            .counts(self.count_hits(), self.count_misses())
+            .time(timer.elapsed())
+            .keystrokes(std::mem::take(&mut self.keystrokes))
+            .timeline(std::mem::take(&mut self.timeline))
+            .timestamp(unix_now())
+            .build();
+        Ok(Some(result))
+    }
+
+    /// Samples the timeline, then returns true if the test is finished. Sampling comes first, so
+    /// that the last second of a timed test is recorded too.
+    fn tick(&mut self) -> bool {
+        self.sample_timeline();
+        self.is_finished()
+    }
+
+    /// Records a sample of the live WPM and the errors made during each second of the test that
+    /// has passed since the last sample. Timed tests are sampled up to their duration.
+    fn sample_timeline(&mut self) {
+        let Some(timer) = self.timer else {
+            return;
+        };
+        let elapsed = match self.mode {
+            TestMode::Time(duration) => timer.elapsed().min(duration),
+            _ => timer.elapsed(),
+        };
+        while elapsed >= Duration::from_secs(self.timeline.len() as u64 + 1) {
+            let second = self.timeline.len() as u64;
+            let errors = self
+                .keystrokes
+                .iter()
+                .rev()
+                .take_while(|k| k.time >= Duration::from_secs(second))
+                .filter(|k| k.time < Duration::from_secs(second + 1))
+                .filter(|k| k.typed.is_some() && !k.is_hit())
+                .count();
+            self.timeline.push(Sample {
+                wpm: self.live_wpm.sample(),
+                errors,
+            });
+        }
+    }
+
+    /// Renders a single frame of the test: the mode badge, indicators, and the textbox. Leaves
+    /// the terminal cursor at the current typing position.
+    fn render_frame(
+        &mut self,
+        stdout: &mut Stdout,
+        config: &Config,
+        frame_time: Duration,
+    ) -> Result<(), std::io::Error> {
+        // render mode info
+        self.render_mode(stdout)?;
+
+        // render performance indicator
+        if config.get_bool("show performance indicator") {
+            let perf_factor = frame_time.as_secs_f32() / 0.1;
+            queue!(
+                stdout,
+                MoveRight(1),
+                Print("".with(color_lerp((0, 255, 0), (255, 0, 0), perf_factor)))
+            )?;
+        }
+
+        // render live wpm
+        if config.get_bool("show live words per minute") {
+            queue!(
+                stdout,
+                MoveRight(1),
+                Print(format!("WPM: {:>3.1}", self.live_wpm.wpm() as usize).on_dark_grey())
+            )?;
+        }
+
+        // hide cursor and render textbox
+        queue!(stdout, Hide)?;
+        let layout = self.layout();
+        let scroll = self.scroll(&layout);
+        self.render_textbox(stdout, &layout, scroll)?;
+
+        // move cursor to the typing position
+        let (col, row) = layout[self.cursor.min(self.letters.len())];
+        queue!(
+            stdout,
+            MoveTo(
+                self.text_limit.0 .0 + col,
+                self.text_limit.0 .1 + row.saturating_sub(scroll)
+            ),
+            Show
+        )?;
+        Ok(())
+    }
+
+    /// Returns true once the end condition of this test's mode has been reached.
+    fn is_finished(&self) -> bool {
         match self.mode {
-            TestMode::Words(_) => {
-                if self.cursor < self.phrase.len() {
-                    return Ok(None);
-                }
-            }
             TestMode::Time(duration) => {
                 if let Some(timer) = self.timer {
-                    if timer.elapsed() < duration {
-                        return Ok(None);
-                    }
+                    timer.elapsed() >= duration
                 } else {
-                    return Ok(None);
+                    false
                 }
             }
+            _ => !self.cursor_in_bounds(),
         }
-
-        // get timer and wpm for score report, since the test was not terminated prematurely
-        let timer = self.timer.expect("Timer unexpectedly uninitialized.");
-        let wpm = match self.mode {
-            TestMode::Words(_) => (
-                wpm_gross(self.phrase.len(), timer.elapsed()),
-                wpm_net(self.phrase.len(), self.count_misses(), timer.elapsed()),
-            ),
-            TestMode::Time(_) => (
-                wpm_gross(self.cursor, timer.elapsed()),
-                wpm_net(self.cursor, self.count_misses(), timer.elapsed()),
-            ),
-        };
-
-        // create test result
-        let result = TestResult::new(
-            self.phrase.split_whitespace().count(),
-            self.wordlist,
-            self.mode.clone(),
-            self.count_hits(),
-            self.count_misses(),
-            timer.elapsed(),
-            wpm,
-        );
-        Ok(Some(result))
     }
 
-    /// Handles a keypress.
+    /// Handles a keypress, and records it in the keystroke log.
     fn handle_key(&mut self, key: KeyEvent) {
         // if timer hasn't started, the first kepress should start it
-        if self.timer.is_none() {
-            self.timer = Some(Instant::now());
-        }
+        let timer = *self.timer.get_or_insert_with(Instant::now);
+        let time = timer.elapsed();
 
         // handle keypress
         use KeyCode::*;
         match key.code {
             Backspace => {
                 // prevent us from deleting into nowhere
-                if self.cursor <= 0 {
+                if self.cursor == 0 {
                     return;
                 }
                 self.cursor -= 1;
                 let cursor_letter = self.letters.get_mut(self.cursor).unwrap();
-                *cursor_letter = Letter::Char(match *cursor_letter {
-                    Letter::Char(c) => c,
-                    Letter::Hit(c) => c,
-                    Letter::Miss(c) => c,
+                let (c, correction) = (
+                    cursor_letter.char(),
+                    matches!(cursor_letter, Letter::Miss(..)),
+                );
+                *cursor_letter = Letter::Char(c);
+                self.keystrokes.push(Keystroke {
+                    time,
+                    position: self.cursor,
+                    expected: c,
+                    typed: None,
+                    backspace: true,
+                    correction,
                 });
             }
-            Char(c) => {
-                let cursor_letter = self.letters.get_mut(self.cursor).unwrap();
-                if let Letter::Char(cursor_char) = *cursor_letter {
-                    if c == cursor_char {
-                        // correct keypress
-                        *cursor_letter = Letter::Hit(cursor_char);
-                    } else if c == ' ' {
-                        // early space - jump to end
-                        self.jump_to_end();
-                    } else if cursor_char == ' ' {
-                        // don't allow progression past an error
-                        return;
-                    } else {
-                        // incorrect keypress
-                        *cursor_letter = Letter::Miss(cursor_char);
-                    }
-                    self.cursor += 1;
-                }
-            }
+            Enter => self.type_char('\n', time),
+            Char(c) => self.type_char(c, time),
             _ => {}
         }
     }
 
-    /// Jumps the cursor to the space following the current word.
+    /// Types a character at the cursor, and records it in the keystroke log.
+    fn type_char(&mut self, c: char, time: Duration) {
+        // prevent us from typing past the end of the phrase
+        let Some(cursor_letter) = self.letters.get_mut(self.cursor) else {
+            return;
+        };
+        if let Letter::Char(cursor_char) = *cursor_letter {
+            self.keystrokes.push(Keystroke {
+                time,
+                position: self.cursor,
+                expected: cursor_char,
+                typed: Some(c),
+                backspace: false,
+                correction: false,
+            });
+            if c == cursor_char {
+                // correct keypress
+                *cursor_letter = Letter::Hit(cursor_char);
+            } else if cursor_char == '\n' {
+                // don't allow progression to the next line past an error
+                return;
+            } else if c == ' ' {
+                // early space - jump to end
+                self.jump_to_end();
+            } else if cursor_char == ' ' {
+                // don't allow progression past an error
+                return;
+            } else {
+                // incorrect keypress
+                *cursor_letter = Letter::Miss(cursor_char, Some(c));
+            }
+            self.cursor += 1;
+
+            // after a newline, skip the indentation of the next line if enabled
+            if self.skip_indentation && matches!(self.letters[self.cursor - 1], Letter::Hit('\n')) {
+                self.skip_to_text();
+            }
+        }
+    }
+
+    /// Skips the cursor over any spaces ahead of it. They weren't typed, so they are marked as
+    /// skipped rather than hit.
+    fn skip_to_text(&mut self) {
+        while let Some(letter @ Letter::Char(' ')) = self.letters.get_mut(self.cursor) {
+            *letter = Letter::Skipped(' ');
+            self.cursor += 1;
+        }
+    }
+
+    /// Jumps the cursor past the space following the current word. At the end of a line, the
+    /// cursor stops on the newline instead, since newlines require enter.
     fn jump_to_end(&mut self) {
-        // jump cursor to the nearest space
+        // jump cursor to the nearest space or newline
         for (i, l) in self
             .letters
             .iter_mut()
@@ -300,55 +398,96 @@ impl TestRenderer {
                     self.cursor = i;
                     return;
                 }
+                Letter::Char('\n') => {
+                    // the cursor is advanced after this, so stop just before the newline
+                    self.cursor = i - 1;
+                    return;
+                }
                 Letter::Char(c) => {
-                    *l = Letter::Miss(*c);
+                    *l = Letter::Miss(*c, None);
                 }
                 _ => {}
             }
         }
-        self.cursor = self.letters.len();
+        self.cursor = self.letters.len().saturating_sub(1);
+    }
+
+    /// Computes the position of every letter within the textbox, as (column, row). Lines wrap at
+    /// the textbox width, and break after newlines. One extra position is included at the end, for
+    /// a cursor that has passed the last letter.
+    fn layout(&self) -> Vec<(u16, u16)> {
+        let width = self.text_limit.1 .0.max(1);
+        let (mut col, mut row) = (0, 0);
+        let mut positions = Vec::with_capacity(self.letters.len() + 1);
+        for letter in &self.letters {
+            if col >= width {
+                (col, row) = (0, row + 1);
+            }
+            positions.push((col, row));
+            if letter.char() == '\n' {
+                (col, row) = (0, row + 1);
+            } else {
+                col += 1;
+            }
+        }
+        if col >= width {
+            (col, row) = (0, row + 1);
+        }
+        positions.push((col, row));
+        positions
     }
 
-    fn render_textbox(&self, stdout: &mut Stdout) -> Result<(), std::io::Error> {
+    /// Gets the first visible row of the textbox, scrolled so that the cursor stays on screen with
+    /// a line of context above it where possible.
+    fn scroll(&self, layout: &[(u16, u16)]) -> u16 {
+        let (_, row) = layout[self.cursor.min(self.letters.len())];
+        row.saturating_sub(if self.text_limit.1 .1 > 1 { 1 } else { 0 })
+    }
+
+    fn render_textbox(
+        &self,
+        stdout: &mut Stdout,
+        layout: &[(u16, u16)],
+        scroll: u16,
+    ) -> Result<(), std::io::Error> {
+        // clear visible lines, along with the overflow indicator below them
+        let ((x, y), (_, lines)) = self.text_limit;
+        for line in 0..=lines {
+            queue!(stdout, MoveTo(x, y + line), Clear(ClearType::UntilNewLine))?;
+        }
+
         // render characters
-        let mut letters_on_line = 0;
-        let mut lines_on_screen = 0;
-        for (idx, letter) in self.letters.iter().enumerate() {
-            // if there's too many letters on this line, go to next line
-            if letters_on_line >= self.text_limit.1 .0 {
-                lines_on_screen += 1;
-                letters_on_line = 0;
-                queue!(
-                    stdout,
-                    MoveTo(self.text_limit.0 .0, self.text_limit.0 .1 + lines_on_screen)
-                )?;
+        for (idx, (letter, (col, row))) in self.letters.iter().zip(layout).enumerate() {
+            // skip lines that have been scrolled past
+            if *row < scroll {
+                continue;
             }
 
             // if there's too many lines, cut off here
-            if lines_on_screen >= self.text_limit.1 .1 {
+            if *row >= scroll + lines {
                 queue!(
                     stdout,
-                    MoveTo(
-                        self.screen_size.0 / 2,
-                        self.text_limit.0 .1 + lines_on_screen
-                    ),
+                    MoveTo(self.screen_size.0 / 2, y + lines),
                     Print("...")
                 )?;
                 break;
             }
 
-            // render letter
+            // render letter. newlines are drawn as a symbol at the end of their line
+            queue!(stdout, MoveTo(x + col, y + row - scroll))?;
+            let display = |c: char| if c == '\n' { '↵' } else { c };
             use Letter::*;
             match *letter {
-                Char(c) => queue!(stdout, Print(c.dark_grey().on_grey()))?,
+                Char(c) => queue!(stdout, Print(display(c).dark_grey().on_grey()))?,
                 Hit(c) => {
                     let char_age = self.cursor as i32 - idx as i32;
                     let color = color_lerp((90, 255, 50), (30, 200, 30), char_age as f32 / 50.);
-                    queue!(stdout, Print(c.black().on(color).italic()))?
+                    queue!(stdout, Print(display(c).black().on(color).italic()))?
                 }
-                Miss(c) => queue!(stdout, Print(c.black().on_red()))?,
+                Miss(c, Some(_)) => queue!(stdout, Print(display(c).black().on_red()))?,
+                Miss(c, None) => queue!(stdout, Print(display(c).black().on_dark_red()))?,
+                Skipped(c) => queue!(stdout, Print(display(c)))?,
             }
-            letters_on_line += 1;
         }
 
         // done
@@ -377,6 +516,37 @@ impl TestRenderer {
                     )
                 )?;
             }
+            TestMode::Quote(length) => {
+                queue!(
+                    stdout,
+                    Print(format!(" QUOTE [{length}]").on_dark_cyan().white())
+                )?;
+            }
+            TestMode::Code(language) => {
+                queue!(
+                    stdout,
+                    Print(format!(" CODE [{language}]").on_dark_blue().white())
+                )?;
+            }
+            TestMode::Adaptive(_) => {
+                queue!(stdout, Print(" ADAPTIVE".on_dark_red().white()))?;
+            }
+            TestMode::Drill { ref ngram, .. } => {
+                queue!(
+                    stdout,
+                    Print(format!(" DRILL [{ngram}]").on_dark_red().white())
+                )?;
+            }
+            TestMode::Custom { .. } => {
+                queue!(
+                    stdout,
+                    Print(
+                        format!(" CUSTOM [{}]", self.mode.name())
+                            .on_dark_yellow()
+                            .white()
+                    )
+                )?;
+            }
         }
         Ok(())
     }
@@ -396,10 +566,158 @@ impl TestRenderer {
     fn count_misses(&self) -> usize {
         let mut misses = 0;
         for l in &*self.letters {
-            if let Letter::Miss(_) = l {
+            if let Letter::Miss(..) = l {
                 misses += 1;
             }
         }
         misses
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    /// Creates a test renderer over the given phrase.
+    fn create_test_renderer(phrase: &str) -> TestRenderer {
+        TestRenderer::new(Wordlist::English1k, phrase.into(), TestMode::Words(2))
+    }
+
+    /// Sends a key to the given renderer.
+    fn press(renderer: &mut TestRenderer, code: KeyCode) {
+        renderer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn keystrokes_are_recorded() {
+        let mut renderer = create_test_renderer("ab cd");
+        press(&mut renderer, KeyCode::Char('a'));
+        press(&mut renderer, KeyCode::Char('x'));
+        press(&mut renderer, KeyCode::Backspace);
+        press(&mut renderer, KeyCode::Char('b'));
+
+        // every keypress should be logged in order, with the expected and typed characters
+        let log = &renderer.keystrokes;
+        assert_eq!(log.len(), 4);
+        assert_eq!((log[0].expected, log[0].typed), ('a', Some('a')));
+        assert_eq!((log[1].expected, log[1].typed), ('b', Some('x')));
+        assert!(log[2].backspace && log[2].correction && log[2].typed.is_none());
+        assert_eq!((log[3].position, log[3].typed), (1, Some('b')));
+        assert!(log.windows(2).all(|w| w[0].time <= w[1].time));
+    }
+
+    #[test]
+    fn misses_record_typed_char() {
+        let mut renderer = create_test_renderer("abc de");
+        press(&mut renderer, KeyCode::Char('x'));
+        press(&mut renderer, KeyCode::Char(' '));
+
+        // mistyped letters store what was typed, and letters skipped over store nothing
+        assert!(matches!(renderer.letters[0], Letter::Miss('a', Some('x'))));
+        assert!(matches!(renderer.letters[1], Letter::Miss('b', None)));
+        assert!(matches!(renderer.letters[2], Letter::Miss('c', None)));
+    }
+
+    #[test]
+    fn keystrokes_past_end_are_ignored() {
+        let mut renderer = create_test_renderer("a");
+        press(&mut renderer, KeyCode::Char('a'));
+        press(&mut renderer, KeyCode::Char('a'));
+        assert_eq!(renderer.keystrokes.len(), 1);
+    }
+
+    #[test]
+    fn applying_keystrokes_reproduces_test() {
+        let mut original = create_test_renderer("ab cd");
+        for c in ['a', 'x', ' ', 'c', 'd'] {
+            press(&mut original, KeyCode::Char(c));
+        }
+        press(&mut original, KeyCode::Backspace);
+        press(&mut original, KeyCode::Char('d'));
+
+        // replaying the log into a fresh renderer should end in the same state
+        let mut replay = create_test_renderer("ab cd");
+        for keystroke in &original.keystrokes {
+            replay.apply(keystroke);
+        }
+        assert_eq!(replay.cursor, original.cursor);
+        assert_eq!(replay.count_hits(), original.count_hits());
+        assert_eq!(replay.count_misses(), original.count_misses());
+        assert_eq!(replay.keystrokes.len(), original.keystrokes.len());
+    }
+
+    #[test]
+    fn non_ascii_phrases_are_typeable() {
+        let mut renderer = create_test_renderer("café ok");
+        for c in "café".chars() {
+            press(&mut renderer, KeyCode::Char(c));
+        }
+        assert_eq!(renderer.count_hits(), 4);
+        assert_eq!(renderer.cursor, 4);
+    }
+
+    #[test]
+    fn layout_breaks_after_newlines() {
+        let mut renderer = create_test_renderer("ab\n cd");
+        renderer.text_limit = ((0, 0), (3, 2));
+        assert_eq!(
+            renderer.layout(),
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn newlines_require_enter_and_skip_indentation() {
+        let mut renderer = create_test_renderer("a\n  b");
+        renderer.skip_indentation = true;
+        press(&mut renderer, KeyCode::Char('a'));
+
+        // anything but enter is blocked at the end of a line
+        press(&mut renderer, KeyCode::Char(' '));
+        assert_eq!(renderer.cursor, 1);
+
+        // enter moves to the next line, past its indentation, which doesn't count as typed
+        press(&mut renderer, KeyCode::Enter);
+        assert_eq!(renderer.cursor, 4);
+        assert!(matches!(renderer.letters[2], Letter::Skipped(' ')));
+        assert_eq!(renderer.count_hits(), 2);
+
+        // replaying reproduces skipped indentation without the setting
+        let mut replay = create_test_renderer("a\n  b");
+        press(&mut renderer, KeyCode::Char('b'));
+        for keystroke in &renderer.keystrokes {
+            replay.apply(keystroke);
+        }
+        assert_eq!(replay.cursor, renderer.cursor);
+        assert_eq!(replay.count_hits(), renderer.count_hits());
+    }
+
+    #[test]
+    fn timed_tests_sample_every_second() {
+        let mut renderer = TestRenderer::new(
+            Wordlist::English1k,
+            "ab cd".into(),
+            TestMode::Time(Duration::from_secs(3)),
+        );
+        renderer.timer = Instant::now().checked_sub(Duration::from_millis(3500));
+        assert!(renderer.tick());
+        assert_eq!(renderer.timeline.len(), 3);
+    }
+
+    #[test]
+    fn early_space_stops_at_newline() {
+        let mut renderer = create_test_renderer("ab\ncd");
+        press(&mut renderer, KeyCode::Char('a'));
+
+        // the rest of the word is missed, but the newline is left for enter
+        press(&mut renderer, KeyCode::Char(' '));
+        assert_eq!(renderer.cursor, 2);
+        assert!(matches!(renderer.letters[1], Letter::Miss('b', None)));
+        assert!(matches!(renderer.letters[2], Letter::Char('\n')));
+        press(&mut renderer, KeyCode::Char(' '));
+        assert_eq!(renderer.cursor, 2);
+        press(&mut renderer, KeyCode::Enter);
+        assert_eq!(renderer.cursor, 3);
+    }
+}
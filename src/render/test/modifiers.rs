@@ -0,0 +1,138 @@
+use crate::config::Config;
+use rand::Rng;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Punctuation that may be appended to a word, along with the chance of doing so.
+const TRAILING: [(char, f64); 4] = [(',', 0.1), ('.', 0.06), ('?', 0.02), ('!', 0.02)];
+
+/// Pairs that may wrap a word, along with the chance of doing so.
+const WRAPPING: [((char, char), f64); 3] =
+    [(('"', '"'), 0.04), (('(', ')'), 0.02), (('[', ']'), 0.01)];
+
+/// Chance for a word to be replaced by a number.
+const NUMBER_CHANCE: f64 = 0.1;
+
+/// Transformations applied to phrases generated from wordlists.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Modifiers {
+    /// Capitalize sentences, and add punctuation around words.
+    pub punctuation: bool,
+    /// Replace some words with numbers.
+    pub numbers: bool,
+}
+
+impl Modifiers {
+    /// Gets the modifiers enabled in the given configuration.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            punctuation: config.get_bool("include punctuation"),
+            numbers: config.get_bool("include numbers"),
+        }
+    }
+
+    /// Returns true if no modifiers are enabled.
+    pub fn is_empty(&self) -> bool {
+        !self.punctuation && !self.numbers
+    }
+
+    /// Applies these modifiers to a phrase of space-separated words. The number of words is kept
+    /// the same.
+    pub fn apply(&self, phrase: &str, rng: &mut impl Rng) -> String {
+        let words = phrase.split(' ').collect::<Vec<&str>>();
+        let mut out = Vec::with_capacity(words.len());
+        let mut sentence_start = true;
+        for (idx, word) in words.iter().enumerate() {
+            // replace with a number
+            let mut word = if self.numbers && rng.random_bool(NUMBER_CHANCE) {
+                rng.random_range(0..10000).to_string()
+            } else {
+                word.to_string()
+            };
+
+            // add punctuation
+            if self.punctuation {
+                if sentence_start {
+                    word = capitalize(&word);
+                }
+                for ((open, close), chance) in WRAPPING {
+                    if rng.random_bool(chance) {
+                        word = format!("{open}{word}{close}");
+                        break;
+                    }
+                }
+                if idx == words.len() - 1 {
+                    word.push('.');
+                } else {
+                    for (c, chance) in TRAILING {
+                        if rng.random_bool(chance) {
+                            word.push(c);
+                            break;
+                        }
+                    }
+                }
+                sentence_start = word.ends_with(['.', '?', '!']);
+            }
+            out.push(word);
+        }
+        out.join(" ")
+    }
+}
+
+impl Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names = vec![];
+        if self.punctuation {
+            names.push("punctuation");
+        }
+        if self.numbers {
+            names.push("numbers");
+        }
+        write!(f, "{}", names.join(", "))
+    }
+}
+
+/// Capitalizes the first letter of a word.
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    const PHRASE: &str = "the quick brown fox jumps over the lazy dog again and again";
+
+    #[test]
+    fn punctuation_forms_sentences() {
+        let modifiers = Modifiers {
+            punctuation: true,
+            numbers: false,
+        };
+        let phrase = modifiers.apply(PHRASE, &mut StdRng::seed_from_u64(0));
+        assert!(phrase.starts_with('T') || phrase.starts_with(['"', '(', '[']));
+        assert!(phrase.ends_with('.'));
+        assert_eq!(phrase.split(' ').count(), PHRASE.split(' ').count());
+    }
+
+    #[test]
+    fn numbers_keep_word_count() {
+        let modifiers = Modifiers {
+            punctuation: false,
+            numbers: true,
+        };
+        let phrase = modifiers.apply(PHRASE, &mut StdRng::seed_from_u64(0));
+        assert_eq!(phrase.split(' ').count(), PHRASE.split(' ').count());
+    }
+
+    #[test]
+    fn no_modifiers_is_identity() {
+        let phrase = Modifiers::default().apply(PHRASE, &mut StdRng::seed_from_u64(0));
+        assert_eq!(phrase, PHRASE);
+    }
+}
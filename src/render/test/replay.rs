@@ -0,0 +1,128 @@
+use super::*;
+use crossterm::{
+    event::KeyModifiers,
+    terminal::{Clear, ClearType},
+};
+
+/// Playback speeds available during a replay, slowest first.
+const SPEEDS: [f32; 3] = [0.5, 1., 2.];
+
+impl TestRenderer {
+    /// Creates a renderer over the phrase of a recorded test, ready to be replayed.
+    pub fn from_result(result: &TestResult) -> Self {
+        Self::new(
+            result.wordlist.clone(),
+            result.phrase.clone(),
+            result.mode.clone(),
+        )
+    }
+
+    /// Applies a recorded keystroke as if it were typed by the user.
+    pub fn apply(&mut self, keystroke: &Keystroke) {
+        // indentation may have been skipped automatically when the test was taken
+        if keystroke.typed.is_some() && self.cursor < keystroke.position {
+            self.skip_to_text();
+        }
+        let code = match keystroke.typed {
+            Some(c) => KeyCode::Char(c),
+            None => KeyCode::Backspace,
+        };
+        self.live_wpm.press();
+        self.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    /// Replays the given keystrokes through the test renderer at their original timing, until the
+    /// user exits. Playback can be paused with space, and slowed down or sped up with the arrow
+    /// keys.
+    pub fn replay(
+        &mut self,
+        keystrokes: &[Keystroke],
+        config: &Config,
+    ) -> Result<(), std::io::Error> {
+        // set up variables for the renderer
+        self.apply_config(config);
+        self.skip_indentation = false; // reproduced from keystroke positions instead
+        self.apply_screen_limits()?;
+        let mut frame_time = Duration::default();
+        let mut stdout = stdout(); // stdout handle
+        clear(&mut stdout);
+
+        // playback state
+        let mut speed = 1; // index into `SPEEDS`
+        let mut paused = false;
+        let mut clock = Duration::ZERO; // position within the recording
+        let mut next = 0; // next keystroke to apply
+        let mut last_tick = Instant::now();
+
+        // play loop
+        loop {
+            // start frametime timer
+            let dt = Instant::now();
+
+            // advance the playback clock, and apply every keystroke that is due
+            if !paused {
+                clock += last_tick.elapsed().mul_f32(SPEEDS[speed]);
+            }
+            last_tick = Instant::now();
+            self.timer = Instant::now().checked_sub(clock);
+            while let Some(keystroke) = keystrokes.get(next) {
+                if keystroke.time > clock {
+                    break;
+                }
+                self.apply(keystroke);
+                next += 1;
+            }
+
+            // render playback status
+            let status = if next >= keystrokes.len() {
+                String::from(" REPLAY [done]")
+            } else if paused {
+                String::from(" REPLAY [paused]")
+            } else {
+                format!(" REPLAY [{}x]", SPEEDS[speed])
+            };
+            queue!(
+                stdout,
+                MoveTo(PAD_X + 1, self.text_limit.0 .1 + self.line_limit + 2),
+                Clear(ClearType::UntilNewLine),
+                Print(status.on_dark_blue().white()),
+                MoveRight(1),
+                Print("space: pause, ←/→: speed, esc: exit".dark_grey()),
+            )?;
+
+            // render frame
+            self.render_frame(&mut stdout, config, frame_time)?;
+
+            // finished rendering, so flush to terminal
+            frame_time = dt.elapsed();
+            stdout.flush()?;
+
+            // handle events
+            if !poll(Duration::from_millis(10))? {
+                continue;
+            } else {
+                use Event::*;
+                use KeyCode::*;
+                match read()? {
+                    Key(key) => match key.code {
+                        Esc | Enter => break,
+                        Char(' ') => paused = !paused,
+                        Left | Char('h') => speed = speed.saturating_sub(1),
+                        Right | Char('l') => speed = (speed + 1).min(SPEEDS.len() - 1),
+                        _ => {}
+                    },
+                    Resize(_, _) => {
+                        clear(&mut stdout);
+                        self.apply_screen_limits()?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        // show cursor, reset color, and clear
+        execute!(stdout, Print("x".reset()), Show)?;
+        clear(&mut stdout);
+        Ok(())
+    }
+}
@@ -1,19 +1,251 @@
+use super::{Modifiers, TestResult};
+use crate::{
+    analysis::Weakness,
+    render::{
+        quote::{self, QuoteLength},
+        snippet::CodeLanguage,
+        util::*,
+        wordlist::Wordlist,
+    },
+};
+use rand::distr::{weighted::WeightedIndex, Distribution};
 use serde_derive::{Deserialize, Serialize};
-use std::{fmt::Display, time::Duration};
+use std::{fmt::Display, path::Path, time::Duration};
 
 /// Represents which mode a test is in.
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub enum TestMode {
     Words(usize),
     Time(Duration),
+    Quote(QuoteLength),
+    /// A multi-line code snippet in the given language.
+    Code(CodeLanguage),
+    /// Custom text, read from the file at `name`. `hash` identifies the text that was typed.
+    Custom {
+        name: String,
+        hash: u64,
+    },
+    /// A number of words from a wordlist, biased towards words containing the keys and bigrams the
+    /// user is weakest on.
+    Adaptive(usize),
+    /// A number of words from a wordlist, all containing the given sequence of characters.
+    Drill {
+        ngram: String,
+        words: usize,
+    },
+}
+
+impl TestMode {
+    /// Creates a custom test mode over the text of the file at `path`.
+    pub fn custom(path: impl Into<String>) -> Result<Self, std::io::Error> {
+        let name = path.into();
+        let hash = hash_str(&read_custom_text(&name)?);
+        Ok(Self::Custom { name, hash })
+    }
+
+    /// Generates a phrase for a test in this mode. Returns the phrase, along with its source when
+    /// it was not generated from the given wordlist. Adaptive tests are generated from `history`.
+    pub fn phrase(
+        &self,
+        wordlist: &Wordlist,
+        modifiers: Modifiers,
+        history: &[TestResult],
+    ) -> Result<(String, Option<String>), std::io::Error> {
+        use TestMode::*;
+        Ok(match self {
+            Words(length) => (wordlist_phrase(wordlist, *length, modifiers)?, None),
+            Time(_) => (wordlist_phrase(wordlist, 100, modifiers)?, None),
+            Quote(length) => {
+                let quote = quote::Quote::random(*length);
+                (quote.text, Some(quote.source))
+            }
+            Code(language) => (language.random_snippet(), None),
+            Custom { name, .. } => (read_custom_text(name)?, Some(name.clone())),
+            Adaptive(length) => {
+                let weakness = Weakness::from_history(history);
+                (
+                    adaptive_phrase(wordlist, *length, modifiers, &weakness)?,
+                    None,
+                )
+            }
+            Drill { ngram, words } => (drill_phrase(wordlist, ngram, *words, modifiers)?, None),
+        })
+    }
+
+    /// Short name of this mode, without its parameters.
+    pub fn name(&self) -> String {
+        use TestMode::*;
+        match self {
+            Words(_) => "words".into(),
+            Time(_) => "time".into(),
+            Quote(_) => "quote".into(),
+            Code(_) => "code".into(),
+            Custom { name, .. } => Path::new(name)
+                .file_name()
+                .map(|v| v.to_string_lossy().to_string())
+                .unwrap_or(name.clone()),
+            Adaptive(_) => "adaptive".into(),
+            Drill { .. } => "drill".into(),
+        }
+    }
+
+    /// Returns true if tests in this mode generate their phrase from a wordlist.
+    pub fn uses_wordlist(&self) -> bool {
+        matches!(
+            self,
+            TestMode::Words(_) | TestMode::Time(_) | TestMode::Adaptive(_) | TestMode::Drill { .. }
+        )
+    }
 }
 
 impl Display for TestMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use TestMode::*;
-        match *self {
+        match self {
             Words(count) => write!(f, "words {count}"),
             Time(time) => write!(f, "time {}s", time.as_secs()),
+            Quote(length) => write!(f, "quote {length}"),
+            Code(language) => write!(f, "code {language}"),
+            Custom { .. } => write!(f, "custom {}", self.name()),
+            Adaptive(count) => write!(f, "adaptive {count}"),
+            Drill { ngram, words } => write!(f, "drill '{ngram}' {words}"),
         }
     }
 }
+
+/// Identifies a kind of test whose results are comparable with each other: the same mode, wordlist
+/// and modifiers. The wordlist and modifiers are only kept for modes that use them.
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub struct TestKind {
+    pub mode: TestMode,
+    pub wordlist: Option<Wordlist>,
+    pub modifiers: Modifiers,
+}
+
+impl TestKind {
+    pub fn new(mode: TestMode, wordlist: Wordlist, modifiers: Modifiers) -> Self {
+        if mode.uses_wordlist() {
+            Self {
+                mode,
+                wordlist: Some(wordlist),
+                modifiers,
+            }
+        } else {
+            Self {
+                mode,
+                wordlist: None,
+                modifiers: Modifiers::default(),
+            }
+        }
+    }
+}
+
+impl Display for TestKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mode)?;
+        if let Some(wordlist) = &self.wordlist {
+            write!(f, " ({})", wordlist.name())?;
+        }
+        if !self.modifiers.is_empty() {
+            write!(f, " with {}", self.modifiers)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates a phrase of `n` random words from the given wordlist, with modifiers applied.
+fn wordlist_phrase(
+    wordlist: &Wordlist,
+    n: usize,
+    modifiers: Modifiers,
+) -> Result<String, std::io::Error> {
+    let content = wordlist.as_content()?;
+    let tokens = wordlist_tokens(wordlist, &content)?;
+    Ok(modifiers.apply(&tokens_to_phrase(n, &tokens), &mut rand::rng()))
+}
+
+/// Generates a phrase of `n` words from the given wordlist, with modifiers applied. Words are
+/// picked with a probability that grows with their weakness score, so weak keys come up more often.
+fn adaptive_phrase(
+    wordlist: &Wordlist,
+    n: usize,
+    modifiers: Modifiers,
+    weakness: &Weakness,
+) -> Result<String, std::io::Error> {
+    let content = wordlist.as_content()?;
+    let tokens = wordlist_tokens(wordlist, &content)?;
+
+    // scores are cubed, so that a few weak keys stand out against many average ones
+    let weights = tokens.iter().map(|word| weakness.score(word).powi(3));
+    let index = WeightedIndex::new(weights).map_err(std::io::Error::other)?;
+    let mut rng = rand::rng();
+    let phrase = (0..n)
+        .map(|_| tokens[index.sample(&mut rng)])
+        .collect::<Vec<&str>>()
+        .join(" ");
+    Ok(modifiers.apply(&phrase, &mut rng))
+}
+
+/// Generates a phrase of `n` random words from the given wordlist that contain `ngram`, with
+/// modifiers applied.
+fn drill_phrase(
+    wordlist: &Wordlist,
+    ngram: &str,
+    n: usize,
+    modifiers: Modifiers,
+) -> Result<String, std::io::Error> {
+    let content = wordlist.as_content()?;
+    let tokens = wordlist_tokens(wordlist, &content)?
+        .into_iter()
+        .filter(|word| word.to_lowercase().contains(ngram))
+        .collect::<Vec<&str>>();
+    if tokens.is_empty() {
+        return Err(std::io::Error::other(format!(
+            "wordlist '{}' has no words containing '{ngram}'",
+            wordlist.name()
+        )));
+    }
+    Ok(modifiers.apply(&tokens_to_phrase(n, &tokens), &mut rand::rng()))
+}
+
+/// Splits wordlist content into words, failing if there are none.
+fn wordlist_tokens<'a>(
+    wordlist: &Wordlist,
+    content: &'a str,
+) -> Result<Vec<&'a str>, std::io::Error> {
+    let tokens = str_to_tokens(content);
+    if tokens.is_empty() {
+        return Err(std::io::Error::other(format!(
+            "wordlist '{}' contains no words",
+            wordlist.name()
+        )));
+    }
+    Ok(tokens)
+}
+
+/// Reads the text of a custom test from the file at `path`, with whitespace normalized.
+fn read_custom_text(path: &str) -> Result<String, std::io::Error> {
+    let text = normalize_whitespace(&std::fs::read_to_string(path)?);
+    if text.is_empty() {
+        return Err(std::io::Error::other(format!("'{path}' contains no text")));
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drills_contain_ngram() {
+        let mode = TestMode::Drill {
+            ngram: "th".into(),
+            words: 10,
+        };
+        let (phrase, _) = mode
+            .phrase(&Wordlist::English1k, Modifiers::default(), &[])
+            .unwrap();
+        assert_eq!(phrase.split(' ').count(), 10);
+        assert!(phrase.split(' ').all(|word| word.contains("th")));
+    }
+}
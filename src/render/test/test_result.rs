@@ -1,6 +1,32 @@
-use crate::render::{test::TestMode, wordlist::Wordlist};
+use crate::render::{
+    test::{Keystroke, Letter, Modifiers, TestKind, TestMode, TestRenderer},
+    util::*,
+    wordlist::Wordlist,
+};
 use serde_derive::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
+
+/// Counts of characters by how they ended up at the end of a test.
+#[derive(Debug, Default, PartialEq)]
+pub struct CharCounts {
+    /// Characters typed correctly.
+    pub correct: usize,
+    /// Characters typed incorrectly, and left uncorrected.
+    pub incorrect: usize,
+    /// Characters typed and later erased with backspace.
+    pub extra: usize,
+    /// Characters skipped over with an early space.
+    pub missed: usize,
+}
+
+/// Live performance over a single second of a test.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Sample {
+    /// Live WPM at the end of the second.
+    pub wpm: f32,
+    /// Errors made during the second.
+    pub errors: usize,
+}
 
 /// Contains all information about a test result, including performance metrics.
 #[derive(Serialize, Deserialize)]
@@ -11,6 +37,15 @@ pub struct TestResult {
     pub wordlist: Wordlist,
     /// Mode used.
     pub mode: TestMode,
+    /// Phrase the test was taken on.
+    #[serde(default)]
+    pub phrase: String,
+    /// Source of the phrase, when it was not generated from the wordlist.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Modifiers applied to the phrase.
+    #[serde(default)]
+    pub modifiers: Modifiers,
     /// Hit count.
     pub hits: usize,
     /// Miss count.
@@ -19,26 +54,340 @@ pub struct TestResult {
     pub time: Duration,
     /// Calculated WPMs, in (gross, net) format.
     pub wpm: (f32, f32),
+    /// Raw WPM, counting every typed keystroke, including those later corrected.
+    #[serde(default)]
+    pub raw_wpm: f32,
+    /// Percentage of typed keystrokes that were correct.
+    #[serde(default)]
+    pub accuracy: f32,
+    /// Every keystroke made during the test, in order.
+    #[serde(default)]
+    pub keystrokes: Vec<Keystroke>,
+    /// Live WPM and errors, sampled once per second during the test.
+    #[serde(default)]
+    pub timeline: Vec<Sample>,
+    /// Time the test was completed, in seconds since the Unix epoch. Tests recorded before this
+    /// was tracked have no timestamp.
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+}
+
+/// Builds a test result. Performance metrics are calculated from the hits, misses, time and
+/// keystrokes when the result is built.
+pub struct TestResultBuilder {
+    result: TestResult,
+}
+
+impl TestResultBuilder {
+    /// Sets the test length in words.
+    pub fn length(mut self, length: usize) -> Self {
+        self.result.length = length;
+        self
+    }
+
+    /// Sets the phrase the test was taken on.
+    pub fn phrase(mut self, phrase: impl Into<String>) -> Self {
+        self.result.phrase = phrase.into();
+        self
+    }
+
+    /// Sets the modifiers applied to the phrase.
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.result.modifiers = modifiers;
+        self
+    }
+
+    /// Sets the hit and miss counts.
+    pub fn counts(mut self, hits: usize, misses: usize) -> Self {
+        self.result.hits = hits;
+        self.result.misses = misses;
+        self
+    }
+
+    /// Sets the total time taken.
+    pub fn time(mut self, time: Duration) -> Self {
+        self.result.time = time;
+        self
+    }
+
+    /// Sets the keystroke log.
+    pub fn keystrokes(mut self, keystrokes: Vec<Keystroke>) -> Self {
+        self.result.keystrokes = keystrokes;
+        self
+    }
+
+    /// Sets the per-second timeline.
+    pub fn timeline(mut self, timeline: Vec<Sample>) -> Self {
+        self.result.timeline = timeline;
+        self
+    }
+
+    /// Sets the time the test was completed, in seconds since the Unix epoch.
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.result.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Calculates performance metrics, and returns the finished result.
+    pub fn build(mut self) -> TestResult {
+        self.result.calculate();
+        self.result
+    }
+}
+
+impl TestResult {
+    /// Starts building a result for a test in the given mode and wordlist. Everything else starts
+    /// out empty.
+    pub fn builder(mode: TestMode, wordlist: Wordlist) -> TestResultBuilder {
+        TestResultBuilder {
+            result: Self {
+                length: 0,
+                wordlist,
+                mode,
+                phrase: String::new(),
+                source: None,
+                modifiers: Modifiers::default(),
+                hits: 0,
+                misses: 0,
+                time: Duration::ZERO,
+                wpm: (0., 0.),
+                raw_wpm: 0.,
+                accuracy: 0.,
+                keystrokes: vec![],
+                timeline: vec![],
+                timestamp: None,
+            },
+        }
+    }
+
+    /// Gets the kind of test this result was taken on.
+    pub fn kind(&self) -> TestKind {
+        TestKind::new(self.mode.clone(), self.wordlist.clone(), self.modifiers)
+    }
+
+    /// Returns true if `other` records the same test as this result, such as when a profile was
+    /// copied between machines, or the same results were imported twice.
+    pub fn is_same_test(&self, other: &TestResult) -> bool {
+        self.timestamp == other.timestamp
+            && self.mode == other.mode
+            && self.wordlist == other.wordlist
+            && self.phrase == other.phrase
+            && self.hits == other.hits
+            && self.misses == other.misses
+            && self.time == other.time
+    }
+
+    /// Returns true if this test can be replayed. Tests recorded before keystrokes were logged
+    /// cannot be.
+    pub fn is_replayable(&self) -> bool {
+        !self.phrase.is_empty() && !self.keystrokes.is_empty()
+    }
+
+    /// Calculates performance metrics from the hits, misses, time and keystrokes of this result.
+    /// Results without a keystroke log fall back on hits and misses for raw WPM and accuracy.
+    pub fn calculate(&mut self) {
+        // every letter before the cursor is either a hit or a miss at the end of a test
+        let typed = self.hits + self.misses;
+        self.wpm = (
+            wpm_gross(typed, self.time),
+            wpm_net(typed, self.misses, self.time),
+        );
+
+        // raw wpm and accuracy
+        let keystrokes = self
+            .keystrokes
+            .iter()
+            .filter(|k| k.typed.is_some())
+            .collect::<Vec<&Keystroke>>();
+        if keystrokes.is_empty() {
+            self.raw_wpm = self.wpm.0;
+            self.accuracy = accuracy(self.hits, typed);
+        } else {
+            let correct = keystrokes.iter().filter(|k| k.is_hit()).count();
+            self.raw_wpm = wpm_gross(keystrokes.len(), self.time);
+            self.accuracy = accuracy(correct, keystrokes.len());
+        }
+    }
 }
 
 impl TestResult {
-    pub fn new(
-        length: usize,
-        wordlist: Wordlist,
-        mode: TestMode,
-        hits: usize,
-        misses: usize,
-        time: Duration,
-        wpm: (f32, f32),
-    ) -> Self {
-        Self {
-            length,
-            wordlist,
-            mode,
-            hits,
-            misses,
-            time,
-            wpm,
+    /// WPM over each second of the test. This is the sampled live WPM when available, and is
+    /// otherwise computed from the keystroke log, in which case the last second may be partial,
+    /// and is scaled accordingly.
+    pub fn wpm_per_second(&self) -> Vec<f32> {
+        if !self.timeline.is_empty() {
+            return self.timeline.iter().map(|sample| sample.wpm).collect();
+        }
+        let seconds = self.time.as_secs_f32().ceil() as usize;
+        let mut counts = vec![0; seconds];
+        for keystroke in self.keystrokes.iter().filter(|k| k.typed.is_some()) {
+            if let Some(count) =
+                counts.get_mut((keystroke.time.as_secs() as usize).min(seconds - 1))
+            {
+                *count += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, count)| {
+                let second = self
+                    .time
+                    .saturating_sub(Duration::from_secs(i as u64))
+                    .min(Duration::from_secs(1));
+                wpm_gross(count, second)
+            })
+            .collect()
+    }
+
+    /// Change in WPM between the first and last third of the test, as a fraction of the first.
+    /// Negative values mean the user slowed down over the course of the test. Returns `None` for
+    /// tests too short to split.
+    pub fn fatigue(&self) -> Option<f32> {
+        let samples = self.wpm_per_second();
+        let third = samples.len() / 3;
+        if third == 0 {
+            return None;
+        }
+        let mean = |v: &[f32]| v.iter().sum::<f32>() / v.len() as f32;
+        let first = mean(&samples[..third]);
+        if first <= 0. {
+            return None;
+        }
+        Some(mean(&samples[samples.len() - third..]) / first - 1.)
+    }
+
+    /// Coefficient of variation of the per-second WPM of this test. Lower is more consistent.
+    pub fn consistency(&self) -> Option<f32> {
+        coefficient_of_variation(&self.wpm_per_second())
+    }
+
+    /// Counts characters by how they ended up, by replaying the keystroke log. Results without a
+    /// keystroke log fall back on hits and misses.
+    pub fn char_counts(&self) -> CharCounts {
+        if self.keystrokes.is_empty() {
+            return CharCounts {
+                correct: self.hits,
+                incorrect: self.misses,
+                ..Default::default()
+            };
         }
+        let mut renderer = TestRenderer::from_result(self);
+        for keystroke in &self.keystrokes {
+            renderer.apply(keystroke);
+        }
+        let mut counts = CharCounts {
+            extra: self.keystrokes.iter().filter(|k| k.backspace).count(),
+            ..Default::default()
+        };
+        for letter in &renderer.letters {
+            match letter {
+                Letter::Hit(_) => counts.correct += 1,
+                Letter::Miss(_, Some(_)) => counts.incorrect += 1,
+                Letter::Miss(_, None) => counts.missed += 1,
+                Letter::Char(_) | Letter::Skipped(_) => {}
+            }
+        }
+        counts
+    }
+
+    /// Gets every word of the phrase that was mistyped at least once, including words that were
+    /// later corrected, in order.
+    pub fn mistyped_words(&self) -> Vec<&str> {
+        let missed = self
+            .keystrokes
+            .iter()
+            .filter(|k| k.typed.is_some() && !k.is_hit())
+            .map(|k| k.position)
+            .collect::<HashSet<usize>>();
+        let mut words = vec![];
+        let mut position = 0; // position of the first letter of each word
+        for word in self.phrase.split([' ', '\n']) {
+            let len = word.chars().count();
+            if (position..position + len).any(|p| missed.contains(&p)) {
+                words.push(word);
+            }
+            position += len + 1;
+        }
+        words
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn legacy_results_are_recalculated() {
+        // a legacy result, with wpm calculated from whole seconds
+        let mut result = TestResult::builder(TestMode::Words(2), Wordlist::English1k)
+            .length(2)
+            .counts(9, 1)
+            .time(Duration::from_millis(4900))
+            .build();
+        result.wpm = (30., 15.);
+        result.raw_wpm = 0.;
+
+        // recalculating uses fractional seconds, and falls back on hits and misses
+        result.calculate();
+        assert!((result.wpm.0 - wpm_gross(10, Duration::from_millis(4900))).abs() < f32::EPSILON);
+        assert_eq!(result.raw_wpm, result.wpm.0);
+        assert_eq!(result.accuracy, 90.);
+    }
+
+    #[test]
+    fn results_are_broken_down() {
+        // type "ab cd ef", mistyping 'b' and correcting it, then skipping over "cd"
+        let mut renderer =
+            TestRenderer::new(Wordlist::English1k, "ab cd ef".into(), TestMode::Words(3));
+        for code in [
+            KeyCode::Char('a'),
+            KeyCode::Char('x'),
+            KeyCode::Backspace,
+            KeyCode::Char('b'),
+            KeyCode::Char(' '),
+            KeyCode::Char(' '),
+            KeyCode::Char('e'),
+            KeyCode::Char('x'),
+        ] {
+            renderer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        let result = TestResult::builder(TestMode::Words(3), Wordlist::English1k)
+            .length(3)
+            .phrase("ab cd ef")
+            .counts(renderer.count_hits(), renderer.count_misses())
+            .time(Duration::from_millis(2500))
+            .keystrokes(std::mem::take(&mut renderer.keystrokes))
+            .build();
+
+        let counts = result.char_counts();
+        assert_eq!(
+            counts,
+            CharCounts {
+                correct: 5,
+                incorrect: 1,
+                extra: 1,
+                missed: 2,
+            }
+        );
+        assert_eq!(result.mistyped_words(), vec!["ab", "cd", "ef"]);
+        assert_eq!(result.wpm_per_second().len(), 3);
+    }
+
+    #[test]
+    fn timeline_is_preferred() {
+        let mut result =
+            TestResult::builder(TestMode::Time(Duration::from_secs(6)), Wordlist::English1k)
+                .length(1)
+                .time(Duration::from_secs(6))
+                .build();
+        assert_eq!(result.fatigue(), None);
+        result.timeline = [80., 80., 70., 70., 60., 60.]
+            .into_iter()
+            .map(|wpm| Sample { wpm, errors: 0 })
+            .collect();
+        assert_eq!(result.wpm_per_second().len(), 6);
+        assert_eq!(result.fatigue(), Some(-0.25));
     }
 }
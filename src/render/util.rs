@@ -1,13 +1,13 @@
 use std::{
-    io::Stdout,
-    time::{Duration, Instant},
+    io::{Stdout, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crossterm::{
-    cursor::MoveTo,
+    cursor::{MoveTo, MoveToNextLine, Show},
     event::{poll, read, Event, KeyCode},
-    execute,
-    style::Color,
+    execute, queue,
+    style::{Color, Print, Stylize},
     terminal::{Clear, ClearType},
 };
 use rand::prelude::IndexedRandom;
@@ -59,29 +59,86 @@ pub fn pause(timeout: Option<Duration>) {
     }
 }
 
-/// Move to position by char, with wrap in respect to `size`.
-pub fn move_to_wrap(pos: usize, size: (u16, u16)) -> MoveTo {
-    MoveTo(
-        (pos % size.0 as usize) as u16,
-        (pos / size.0 as usize) as u16,
-    )
+/// Prompts the user for a line of text on a cleared screen. Returns `None` if the prompt was
+/// cancelled with the ESC key.
+pub fn prompt(io: &mut Stdout, label: &str) -> Result<Option<String>, std::io::Error> {
+    use Event::*;
+    let mut input = String::new();
+    loop {
+        // render label and current input
+        clear(io);
+        queue!(
+            io,
+            Print(label.on_dark_grey().grey()),
+            MoveToNextLine(1),
+            Print(format!("> {input}")),
+            Show
+        )?;
+        io.flush()?;
+
+        // handle input
+        if let Key(key) = read()? {
+            match key.code {
+                KeyCode::Enter => return Ok(Some(input)),
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    }
 }
 
-/// Calculate raw WPM from typed characters and time.
-/// WPM values are clamped between 0 and 999.
+/// Calculate gross WPM from typed characters and time.
+/// WPM values are clamped between 0 and 999, and are 0 for tests that took no time.
 pub fn wpm_gross(k: usize, dur: Duration) -> f32 {
-    ((k as f32 / 5.) / (dur.as_secs() as f32 / 60.)).clamp(0., 999.)
+    let minutes = dur.as_secs_f32() / 60.;
+    if minutes <= 0. {
+        return 0.;
+    }
+    ((k as f32 / 5.) / minutes).clamp(0., 999.)
 }
 
 /// Calculate net WPM from typed characters and time, with consideration for errors.
-/// WPM values are clamped between 0 and 999.
+/// WPM values are clamped between 0 and 999, and are 0 for tests that took no time.
 pub fn wpm_net(k: usize, e: usize, dur: Duration) -> f32 {
-    (wpm_gross(k, dur) - (e as f32 / (dur.as_secs() as f32 / 60.))).clamp(0., 999.)
+    let minutes = dur.as_secs_f32() / 60.;
+    if minutes <= 0. {
+        return 0.;
+    }
+    (wpm_gross(k, dur) - (e as f32 / minutes)).clamp(0., 999.)
 }
 
-/// Split a string into a vector of its lines.
+/// Calculate accuracy as a percentage of correct keystrokes out of all keystrokes.
+pub fn accuracy(correct: usize, total: usize) -> f32 {
+    if total == 0 {
+        return 0.;
+    }
+    correct as f32 / total as f32 * 100.
+}
+
+/// Calculate the coefficient of variation of a series of samples, which is their standard deviation
+/// relative to their mean. Returns `None` for fewer than two samples, or samples averaging 0.
+pub fn coefficient_of_variation(samples: &[f32]) -> Option<f32> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let mean = samples.iter().sum::<f32>() / samples.len() as f32;
+    if mean <= 0. {
+        return None;
+    }
+    let variance = samples.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / samples.len() as f32;
+    Some(variance.sqrt() / mean)
+}
+
+/// Split a string into a vector of its lines, skipping blank ones.
 pub fn str_to_tokens(src: &str) -> Vec<&str> {
-    src.lines().collect::<Vec<&str>>()
+    src.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
 }
 
 /// Select `n` number of tokens to create a random phrase.
@@ -94,3 +151,94 @@ pub fn tokens_to_phrase(n: usize, tokens: &Vec<&str>) -> String {
     }
     str.trim().to_string()
 }
+
+/// Collapses every run of whitespace in a string into a single space.
+pub fn normalize_whitespace(src: &str) -> String {
+    src.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Seconds in a day.
+pub const DAY: u64 = 86400;
+
+/// Gets the current time, in seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Converts a number of days since the Unix epoch into a (year, month, day) date, in UTC.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+/// Formats a timestamp, in seconds since the Unix epoch, as a YYYY-MM-DD date in UTC.
+pub fn format_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / DAY) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Hashes a string with 64-bit FNV-1a. Unlike the standard library hasher, this is stable across
+/// builds, so it can be stored in profiles.
+pub fn hash_str(src: &str) -> u64 {
+    src.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wpm_uses_fractional_seconds() {
+        // 50 characters (10 words) in 4.9 seconds
+        let wpm = wpm_gross(50, Duration::from_millis(4900));
+        assert!((wpm - 122.45).abs() < 0.01);
+    }
+
+    #[test]
+    fn wpm_of_zero_duration_is_zero() {
+        assert_eq!(wpm_gross(50, Duration::ZERO), 0.);
+        assert_eq!(wpm_net(50, 2, Duration::ZERO), 0.);
+    }
+
+    #[test]
+    fn net_wpm_subtracts_errors() {
+        let dur = Duration::from_secs(30);
+        assert_eq!(wpm_net(100, 5, dur), wpm_gross(100, dur) - 10.);
+    }
+
+    #[test]
+    fn accuracy_is_percentage() {
+        assert_eq!(accuracy(9, 10), 90.);
+        assert_eq!(accuracy(0, 0), 0.);
+    }
+
+    #[test]
+    fn coefficient_of_variation_is_correct() {
+        assert_eq!(coefficient_of_variation(&[50.]), None);
+        assert_eq!(coefficient_of_variation(&[0., 0.]), None);
+        assert_eq!(coefficient_of_variation(&[60., 60., 60.]), Some(0.));
+        assert_eq!(coefficient_of_variation(&[40., 60.]), Some(0.2));
+    }
+
+    #[test]
+    fn dates_are_converted() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(format_date(1_792_281_600), "2026-10-18");
+    }
+}
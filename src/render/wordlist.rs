@@ -1,36 +1,70 @@
 use flate2::bufread::GzDecoder;
 use serde_derive::{Deserialize, Serialize};
-use std::io::Read;
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+/// Directory scanned for user-supplied wordlists. Like the default profile, this is relative to
+/// the directory WPM is executed in.
+pub const WORDLIST_DIR: &str = "wordlists";
+
+/// File extensions accepted for user-supplied wordlists.
+const EXTENSIONS: [&str; 2] = [".txt.gz", ".txt"];
 
 /// Macro to generate `Wordlist`. This macro also implements utilities for conversions and
 /// iteration, and a function to get wordlist content from a given variant.
 macro_rules! wordlist {
     ($($variant:ident => $content:expr),* $(,)?) => {
         /// Wordlist specifier. Does not contain wordlist data.
-        #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+        #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
         pub enum Wordlist {
-            $($variant),*
+            $($variant,)*
+            /// A user-supplied wordlist, loaded from `WORDLIST_DIR` by name at runtime.
+            User(String),
         }
 
         impl Wordlist {
-            /// Returns an iterator over all enum variants
+            /// Returns an iterator over all built-in enum variants
             pub fn iter() -> impl Iterator<Item = Self> {
-                [$(Self::$variant),*].iter().copied()
+                [$(Self::$variant),*].into_iter()
             }
 
             /// Converts enum to wordlist content.
-            pub fn as_content(&self) -> String {
+            pub fn as_content(&self) -> Result<String, std::io::Error> {
                 use super::wordlist::*;
-                let mut decoder = match self {
-                    $(
-                        Wordlist::$variant => GzDecoder::new(&$content[..])
-                    ),*
-                };
                 let mut buf = String::new();
-                decoder
-                    .read_to_string(&mut buf)
-                    .expect("Failed to decompress requested wordlist.");
-                buf
+                match self {
+                    $(
+                        Wordlist::$variant => {
+                            GzDecoder::new(&$content[..]).read_to_string(&mut buf)?;
+                        }
+                    )*
+                    Wordlist::User(name) => {
+                        let path = Self::user_path(name).ok_or_else(|| {
+                            std::io::Error::new(
+                                std::io::ErrorKind::NotFound,
+                                format!("wordlist '{name}' not found in ./{WORDLIST_DIR}"),
+                            )
+                        })?;
+                        let bytes = fs::read(&path)?;
+                        if path.to_string_lossy().ends_with(".gz") {
+                            GzDecoder::new(&bytes[..]).read_to_string(&mut buf)?;
+                        } else {
+                            buf = String::from_utf8(bytes).map_err(std::io::Error::other)?;
+                        }
+                    }
+                }
+                Ok(buf)
+            }
+
+            /// Name of this wordlist, as shown to the user and stored in configuration.
+            pub fn name(&self) -> String {
+                match self {
+                    $(Self::$variant => stringify!($variant).into(),)*
+                    Self::User(name) => name.clone(),
+                }
             }
         }
 
@@ -38,7 +72,7 @@ macro_rules! wordlist {
             fn from(s: &str) -> Self {
                 match s {
                     $(stringify!($variant) => Self::$variant,)*
-                    _ => panic!("Unknown variant: {}", s)
+                    _ => Self::User(s.into()),
                 }
             }
         }
@@ -54,3 +88,71 @@ wordlist!(
     CodeC => include_bytes!("../../wordlist/code_c.txt.gz"),
     CodeJS => include_bytes!("../../wordlist/code_javascript.txt.gz")
 );
+
+impl Wordlist {
+    /// Returns every available wordlist: all built-in ones, followed by user-supplied ones.
+    pub fn all() -> Vec<Self> {
+        Self::iter().chain(Self::scan(WORDLIST_DIR)).collect()
+    }
+
+    /// Scans `dir` for user-supplied wordlists, sorted by name. Lists that share a name with a
+    /// built-in wordlist are ignored. A missing directory simply yields no lists.
+    pub fn scan(dir: impl AsRef<Path>) -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return vec![];
+        };
+        let mut names = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| list_name(&entry.file_name().to_string_lossy()))
+            .filter(|name| !Self::iter().any(|builtin| builtin.name() == *name))
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+        names.into_iter().map(Self::User).collect()
+    }
+
+    /// Finds the file backing the user-supplied wordlist with the given name.
+    fn user_path(name: &str) -> Option<PathBuf> {
+        EXTENSIONS
+            .iter()
+            .map(|ext| Path::new(WORDLIST_DIR).join(format!("{name}{ext}")))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Gets the wordlist name of a file, or `None` if it isn't a wordlist.
+fn list_name(file_name: &str) -> Option<String> {
+    EXTENSIONS
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(ext))
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for wordlist in Wordlist::iter() {
+            assert_eq!(Wordlist::from(wordlist.name().as_str()), wordlist);
+        }
+        assert_eq!(Wordlist::from("rust"), Wordlist::User("rust".into()));
+    }
+
+    #[test]
+    fn list_names_from_files() {
+        assert_eq!(list_name("rust.txt"), Some("rust".into()));
+        assert_eq!(list_name("api.txt.gz"), Some("api".into()));
+        assert_eq!(list_name("notes.md"), None);
+        assert_eq!(list_name(".txt"), None);
+    }
+
+    #[test]
+    fn builtins_decompress() {
+        for wordlist in Wordlist::iter() {
+            assert!(!wordlist.as_content().unwrap().is_empty());
+        }
+    }
+}